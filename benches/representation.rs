@@ -0,0 +1,138 @@
+//! compares the costs of `Version` and `CompactVersion` for parsing, cloning and
+//! comparing, run with `cargo bench`.
+//!
+//! the "reparse" clone is how `Version::clone` used to work, rendering the version
+//! and parsing it again. the "old layout" rows use a copy of how `Version` used to be
+//! stored, the components in a `Vec` with the wildcard as a `String`. its parser only
+//! splits the string without checking it, so it is a lower bound for the old parsing.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use version_lp::{Version, CompactVersion};
+
+const ROUNDS : u32 = 100_000;
+
+/// a mix of the kind of versions that are found in a package index
+const VERSIONS : [&str; 8] = [
+    "1.0.0", "0.3.12", "2.14.1-rc.1", "10.2", "4.0.0-beta", "1.2.3.4", "3.*", "0.0.1-alpha.7",
+];
+
+/// how the components used to be stored, before `VersionPart::Wildcard` was a `char`
+mod old {
+    use std::cmp::Ordering;
+
+    #[derive(Clone, PartialEq, Eq)]
+    pub enum Part {
+        Number(u32),
+        Wildcard(String),
+    }
+
+    impl Ord for Part {
+        fn cmp(&self, other : &Part) -> Ordering {
+            match (self, other) {
+                (Part::Number(a), Part::Number(b)) => a.cmp(b),
+                (Part::Number(_), Part::Wildcard(_)) => Ordering::Less,
+                (Part::Wildcard(_), Part::Number(_)) => Ordering::Greater,
+                (Part::Wildcard(_), Part::Wildcard(_)) => Ordering::Equal,
+            }
+        }
+    }
+
+    impl PartialOrd for Part {
+        fn partial_cmp(&self, other : &Part) -> Option<Ordering> { Some(self.cmp(other)) }
+    }
+
+    #[derive(Clone)]
+    pub struct Version {
+        parts : Vec<Part>,
+        pre : Option<String>,
+        /// never read, but parsing allocates it the same as it used to
+        #[allow(dead_code)]
+        build : Option<String>,
+    }
+
+    impl Version {
+        pub fn from_str(version : &str) -> Option<Version> {
+            let (version, build) = match version.split_once('+') {
+                Some((version, build)) => (version, Some(build.to_string())),
+                None => (version, None),
+            };
+            let (numbers, pre) = match version.split_once('-') {
+                Some((numbers, pre)) => (numbers, Some(pre.to_string())),
+                None => (version, None),
+            };
+            let parts = numbers.split('.')
+                .map(|part| match part {
+                    "*" => Some(Part::Wildcard(part.to_string())),
+                    _ => part.parse().ok().map(Part::Number),
+                })
+                .collect::<Option<Vec<Part>>>()?;
+            Some(Version { parts, pre, build })
+        }
+
+        /// the same as `Version::cmp` used to be, comparing the components they both have
+        pub fn cmp(&self, other : &Version) -> Ordering {
+            let depth = self.parts.len().min(other.parts.len());
+            for i in 0 .. depth {
+                if self.parts[i] != other.parts[i] { return self.parts[i].cmp(&other.parts[i]); }
+            }
+            match (&self.pre, &other.pre) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => a.cmp(b),
+            }
+        }
+    }
+}
+
+fn bench<F : FnMut()>(name : &str, mut f : F) {
+    // once to warm up
+    f();
+
+    let start = Instant::now();
+    for _ in 0 .. ROUNDS { f(); }
+    let per_round : Duration = start.elapsed() / ROUNDS;
+
+    println!("{:<28} {:>10.2?} per round", name, per_round);
+}
+
+fn main() {
+    let versions : Vec<Version> = VERSIONS.iter().map(|v| Version::from_str(v).unwrap()).collect();
+    let compacts : Vec<CompactVersion> = VERSIONS.iter().map(|v| CompactVersion::from_str(v).unwrap()).collect();
+    let olds : Vec<old::Version> = VERSIONS.iter().map(|v| old::Version::from_str(v).unwrap()).collect();
+
+    bench("parse old layout", || for v in VERSIONS.iter() {
+        black_box(old::Version::from_str(black_box(v)));
+    });
+    bench("parse Version", || for v in VERSIONS.iter() {
+        black_box(Version::from_str(black_box(v)));
+    });
+    bench("parse CompactVersion", || for v in VERSIONS.iter() {
+        black_box(CompactVersion::from_str(black_box(v)));
+    });
+
+    bench("clone Version (reparse)", || for v in versions.iter() {
+        black_box(Version::from_str(&black_box(v).to_string()));
+    });
+    bench("clone old layout", || for v in olds.iter() {
+        black_box(black_box(v).clone());
+    });
+    bench("clone Version", || for v in versions.iter() {
+        black_box(black_box(v).clone());
+    });
+    bench("copy CompactVersion", || for v in compacts.iter() {
+        black_box(*black_box(v));
+    });
+
+    bench("compare old layout", || for a in olds.iter() {
+        for b in olds.iter() { black_box(black_box(a).cmp(black_box(b))); }
+    });
+    bench("compare Version", || for a in versions.iter() {
+        for b in versions.iter() { black_box(black_box(a).cmp(black_box(b))); }
+    });
+    bench("compare CompactVersion", || for a in compacts.iter() {
+        for b in compacts.iter() { black_box(black_box(a).cmp(black_box(b))); }
+    });
+}
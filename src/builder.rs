@@ -0,0 +1,200 @@
+//! a **builder** for making versions without formatting a string, and the errors for
+//! building and changing versions.
+
+use core::fmt;
+
+use crate::prelude::*;
+use crate::version::Version;
+use crate::versionpart::VersionPart;
+use crate::parts::Parts;
+use crate::identifiers::is_valid_label;
+
+/// why a version couldn't be built or changed
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum VersionError {
+    /// the version doesn't have any components
+    NoComponents,
+    /// a component was set at `index` but the ones before it weren't, i.e. a `minor` without a `major`
+    MissingComponent { index : usize },
+    /// there would be a component at `index` after a wildcard
+    AfterWildcard { index : usize },
+    /// the pre-release label isn't made of dot separated alphanumeric identifiers
+    InvalidPreRelease(String),
+    /// the build metadata isn't made of dot separated alphanumeric identifiers
+    InvalidBuild(String),
+}
+
+impl fmt::Display for VersionError {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VersionError::NoComponents => write!(f, "a version needs at least one component"),
+            VersionError::MissingComponent { index } => write!(f, "component {} is set, but not the ones before it", index),
+            VersionError::AfterWildcard { index } => write!(f, "component {} would come after a wildcard", index),
+            VersionError::InvalidPreRelease(pre) => write!(f, "invalid pre-release label '{}'", pre),
+            VersionError::InvalidBuild(build) => write!(f, "invalid build metadata '{}'", build),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for VersionError { }
+
+/// builds up a version from its components.
+///
+/// the rules are checked as it is built, and the first problem is returned by `build`.
+///
+/// ```
+/// # use version_lp::{Version, VersionBuilder};
+/// let version = VersionBuilder::new().major(1).minor(2).wildcard().build().unwrap();
+/// assert_eq!(version.to_string(), "1.2.*");
+///
+/// let version = VersionBuilder::new().major(2).minor(0).patch(0).pre("rc.1").build().unwrap();
+/// assert_eq!(version.to_string(), "2.0.0-rc.1");
+///
+/// assert!(VersionBuilder::new().minor(2).build().is_err());
+/// ```
+#[derive(Clone)]
+pub struct VersionBuilder {
+    parts : Parts,
+    pre : Option<String>,
+    build : Option<String>,
+    prefix : Option<char>,
+    error : Option<VersionError>,
+}
+
+impl Default for VersionBuilder {
+    fn default() -> VersionBuilder {
+        VersionBuilder { parts : Parts::new(), pre : None, build : None, prefix : None, error : None }
+    }
+}
+
+impl VersionBuilder {
+
+    /// creates a builder without any components
+    pub fn new() -> VersionBuilder {
+        VersionBuilder::default()
+    }
+
+    /// sets the first component
+    pub fn major(self, number : u32) -> VersionBuilder {
+        self.set(0, VersionPart::Number(number))
+    }
+
+    /// sets the second component, the first has to be set already
+    pub fn minor(self, number : u32) -> VersionBuilder {
+        self.set(1, VersionPart::Number(number))
+    }
+
+    /// sets the third component, the first two have to be set already
+    pub fn patch(self, number : u32) -> VersionBuilder {
+        self.set(2, VersionPart::Number(number))
+    }
+
+    /// adds a number after the components that are already set
+    pub fn number(self, number : u32) -> VersionBuilder {
+        let index = self.parts.len();
+        self.set(index, VersionPart::Number(number))
+    }
+
+    /// adds a `*` wildcard after the components that are already set, nothing
+    /// can be added after it
+    pub fn wildcard(self) -> VersionBuilder {
+        let index = self.parts.len();
+        self.set(index, VersionPart::Wildcard('*'))
+    }
+
+    /// sets the pre-release label, without the leading `-`
+    pub fn pre(mut self, pre : &str) -> VersionBuilder {
+        if !is_valid_label(pre) { return self.fail(VersionError::InvalidPreRelease(pre.to_string())); }
+        self.pre = Some(pre.to_string());
+        self
+    }
+
+    /// sets the build metadata, without the leading `+`
+    pub fn build_metadata(mut self, build : &str) -> VersionBuilder {
+        if !is_valid_label(build) { return self.fail(VersionError::InvalidBuild(build.to_string())); }
+        self.build = Some(build.to_string());
+        self
+    }
+
+    /// sets the prefix that is used by `to_prefixed_string`, `v` or `V`
+    pub fn prefix(mut self, prefix : char) -> VersionBuilder {
+        self.prefix = Some(prefix);
+        self
+    }
+
+    /// creates the version, or the first problem found while building it
+    pub fn build(self) -> Result<Version, VersionError> {
+        if let Some(error) = self.error { return Err(error); }
+        if self.parts.is_empty() { return Err(VersionError::NoComponents); }
+
+        let mut version = Version::from_parts(self.parts, self.pre, self.build);
+        version.set_prefix(self.prefix);
+        Ok(version)
+    }
+
+    /// sets the component at the index, it can replace a component or add the next one
+    fn set(mut self, index : usize, part : VersionPart) -> VersionBuilder {
+        if let Some(error) = check_part(&self.parts, index, part) { return self.fail(error); }
+
+        if index < self.parts.len() {
+            self.parts[index] = part;
+        } else {
+            self.parts.push(part);
+        }
+        self
+    }
+
+    /// keeps the first problem
+    fn fail(mut self, error : VersionError) -> VersionBuilder {
+        self.error.get_or_insert(error);
+        self
+    }
+}
+
+/// checks the part can be put at the index without breaking the rules, that the
+/// components don't skip any and nothing comes after a wildcard
+pub(crate) fn check_part(parts : &[VersionPart], index : usize, part : VersionPart) -> Option<VersionError> {
+    if index > parts.len() {
+        return Some(VersionError::MissingComponent { index });
+    }
+    // replacing the wildcard is fine, adding after it isn't
+    if index == parts.len() && parts.last().is_some_and(VersionPart::is_wildcard) {
+        return Some(VersionError::AfterWildcard { index });
+    }
+    if part.is_wildcard() && index + 1 < parts.len() {
+        return Some(VersionError::AfterWildcard { index : index + 1 });
+    }
+    None
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////////////////
+// TESTS GO HERE
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds() {
+        let version = VersionBuilder::new().major(1).minor(2).patch(3).number(4)
+            .pre("beta.2").build_metadata("abc").prefix('v')
+            .build().unwrap();
+        assert_eq!(version.to_prefixed_string(), "v1.2.3.4-beta.2+abc");
+
+        // setting a component again replaces it
+        assert_eq!(VersionBuilder::new().major(1).major(3).build().unwrap(), Version::new(&[3]));
+    }
+
+    #[test]
+    fn validates() {
+        assert_eq!(VersionBuilder::new().build().err(), Some(VersionError::NoComponents));
+        assert_eq!(VersionBuilder::new().major(1).patch(3).build().err(), Some(VersionError::MissingComponent { index : 2 }));
+        assert_eq!(VersionBuilder::new().major(1).wildcard().number(3).build().err(), Some(VersionError::AfterWildcard { index : 2 }));
+        assert_eq!(VersionBuilder::new().major(1).pre("rc..1").build().err(), Some(VersionError::InvalidPreRelease("rc..1".to_string())));
+        assert_eq!(VersionBuilder::new().major(1).build_metadata("a+b").build().err(), Some(VersionError::InvalidBuild("a+b".to_string())));
+
+        // the first problem is the one that is returned
+        assert_eq!(VersionBuilder::new().minor(1).pre("").build().err(), Some(VersionError::MissingComponent { index : 1 }));
+    }
+}
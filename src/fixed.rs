@@ -0,0 +1,498 @@
+//! a **fixed** capacity version that never allocates, for when there is no allocator.
+//!
+//! works the same as `Version` for comparing and wildcard matching, but only holds
+//! up to `N` numbers, the common pre-release labels (`alpha`, `beta`, `rc`) and no build
+//! metadata. everything is stored inline so it is `Copy`.
+
+use core::fmt;
+use core::cmp::Ordering;
+
+use crate::identifiers::{find, is_numeric, parse_number, compare_numbers};
+
+#[cfg(feature = "alloc")]
+use crate::version::Version;
+#[cfg(feature = "alloc")]
+use crate::versionpart::VersionPart;
+#[cfg(feature = "alloc")]
+use crate::prelude::*;
+#[cfg(feature = "alloc")]
+use crate::parts::Parts;
+
+/// a `FixedVersion` with room for 4 numbers, which fits nearly every version.
+///
+/// ```
+/// # use version_lp::CompactVersion;
+/// let version = CompactVersion::from_str("2.0.0-rc.1").unwrap();
+/// let copy = version;
+///
+/// assert!(copy < CompactVersion::from_str("2.0.0").unwrap());
+/// assert_eq!(version.to_string(), "2.0.0-rc.1");
+/// ```
+pub type CompactVersion = FixedVersion<4>;
+
+/// a pre-release label packed into a few bytes, `alpha`, `beta` or `rc` with an
+/// optional number, i.e. `beta.2`. sorts the same way as the label would in `Version`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum PreRelease {
+    Alpha(Option<u32>),
+    Beta(Option<u32>),
+    Rc(Option<u32>),
+}
+
+impl PreRelease {
+
+    /// packs a label, `None` if it isn't one of the supported forms
+    pub const fn from_str(label : &str) -> Option<PreRelease> {
+        let dot = find(label.as_bytes(), b'.', 0, label.len());
+        let (name, number) = label.split_at(dot);
+
+        let number = match number.as_bytes() {
+            [] => None,
+            [b'.', digits @ ..] => {
+                if !is_numeric(number.split_at(1).1) { return None; }
+                // a leading zero would be lost when it is rendered again
+                if digits.len() > 1 && digits[0] == b'0' { return None; }
+                match parse_number(number.split_at(1).1) {
+                    Some(number) => Some(number),
+                    None => return None,
+                }
+            },
+            _ => return None,
+        };
+
+        match name.as_bytes() {
+            b"alpha" => Some(PreRelease::Alpha(number)),
+            b"beta" => Some(PreRelease::Beta(number)),
+            b"rc" => Some(PreRelease::Rc(number)),
+            _ => None,
+        }
+    }
+
+    /// the number after the label, `2` in `beta.2`
+    pub const fn number(&self) -> Option<u32> {
+        match self {
+            PreRelease::Alpha(number) | PreRelease::Beta(number) | PreRelease::Rc(number) => *number,
+        }
+    }
+
+    /// the order of the labels, alpha then beta then rc
+    const fn rank(&self) -> u64 {
+        match self {
+            PreRelease::Alpha(_) => 0,
+            PreRelease::Beta(_) => 1,
+            PreRelease::Rc(_) => 2,
+        }
+    }
+
+    /// the same as `Ord`, which isn't a `const fn`
+    const fn compare(&self, other : &PreRelease) -> Ordering {
+        match compare_numbers(self.rank(), other.rank()) {
+            Ordering::Equal => match (self.number(), other.number()) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Less,
+                (Some(_), None) => Ordering::Greater,
+                (Some(a), Some(b)) => compare_numbers(a as u64, b as u64),
+            },
+            ordering => ordering,
+        }
+    }
+}
+
+impl fmt::Display for PreRelease {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        //! prints the label, "rc.1"
+        f.write_str(match self {
+            PreRelease::Alpha(_) => "alpha",
+            PreRelease::Beta(_) => "beta",
+            PreRelease::Rc(_) => "rc",
+        })?;
+        match self.number() {
+            Some(number) => write!(f, ".{}", number),
+            None => Ok(()),
+        }
+    }
+}
+
+/// a version of up to `N` numbers (4 by default), with an optional wildcard at the end
+/// and an optional packed pre-release label.
+///
+/// ```
+/// # use version_lp::FixedVersion;
+/// let running : FixedVersion = FixedVersion::from_str("1.4.2").unwrap();
+/// let manifest : FixedVersion = FixedVersion::from_str("1.5.0").unwrap();
+///
+/// assert!(running < manifest);
+/// assert!(running.is_compatible_with(&FixedVersion::from_str("1.*").unwrap()));
+/// ```
+///
+/// it isn't `Hash`, equality only looks at the numbers both versions have (`1.2 == 1.2.0`,
+/// and `1.* == 1.5-rc`) so there isn't a hash that agrees with it. `to_string` can be used
+/// as a key instead.
+#[derive(Clone, Copy)]
+pub struct FixedVersion<const N : usize = 4> {
+    numbers : [u32; N],
+    len : usize,
+    /// if there is a `*` after the numbers
+    wildcard : bool,
+    pre : Option<PreRelease>,
+}
+
+impl <const N : usize> FixedVersion<N> {
+
+    /// creates a new version from the numbers, `None` if there are more than `N`
+    pub const fn new(numbers : &[u32]) -> Option<FixedVersion<N>> {
+        if numbers.len() > N { return None; }
+
+        let mut version = FixedVersion { numbers : [0; N], len : numbers.len(), wildcard : false, pre : None };
+        let mut i = 0;
+        while i < numbers.len() {
+            version.numbers[i] = numbers[i];
+            i += 1;
+        }
+        Some(version)
+    }
+
+    /// creates a new wildcard version, `*`, which is compatible with everything
+    pub const fn new_wildcard() -> FixedVersion<N> {
+        FixedVersion { numbers : [0; N], len : 0, wildcard : true, pre : None }
+    }
+
+    /// creates a version from a string, `1.2.3`, `1.*` or `1.2.3-beta.2`.
+    ///
+    /// returns `None` if the string isn't a version, has more than `N` numbers or has
+    /// a pre-release label that `PreRelease` can't hold. build metadata isn't supported.
+    ///
+    /// this is a `const fn`, so it can check versions while compiling. the package version
+    /// only parses while it has no build metadata and its label is `alpha`, `beta` or `rc`
+    /// with an optional number, `1.0.0+abc` and `1.0.0-pre.1` fail to compile here.
+    ///
+    /// ```
+    /// # use version_lp::FixedVersion;
+    /// const RUNNING : FixedVersion<3> = FixedVersion::from_str(env!("CARGO_PKG_VERSION")).unwrap();
+    /// const MINIMUM : FixedVersion<3> = FixedVersion::from_str("0.2").unwrap();
+    ///
+    /// const _ : () = assert!(RUNNING.compare(&MINIMUM).is_ge(), "needs at least 0.2");
+    /// ```
+    pub const fn from_str(version : &str) -> Option<FixedVersion<N>> {
+        let mut parsed = FixedVersion { numbers : [0; N], len : 0, wildcard : false, pre : None };
+
+        let bytes = version.as_bytes();
+        let end = find(bytes, b'-', 0, bytes.len());
+        if end < bytes.len() {
+            parsed.pre = match PreRelease::from_str(version.split_at(end + 1).1) {
+                Some(pre) => Some(pre),
+                None => return None,
+            };
+        }
+
+        let mut start = 0;
+        while start <= end {
+            let section_end = find(bytes, b'.', start, end);
+            let section = version.split_at(section_end).0.split_at(start).1;
+
+            if let [b'*'] = section.as_bytes() {
+                // we ignore the rest of the string, same as `Version`
+                parsed.wildcard = true;
+                return Some(parsed);
+            }
+            if !is_numeric(section) { return None; }
+            if parsed.len >= N { return None; }
+
+            parsed.numbers[parsed.len] = match parse_number(section) {
+                Some(number) => number,
+                None => return None,
+            };
+            parsed.len += 1;
+
+            start = section_end + 1;
+        }
+
+        Some(parsed)
+    }
+
+    /// the number of components, the wildcard included
+    pub const fn len(&self) -> usize {
+        self.len + self.wildcard as usize
+    }
+
+    /// checks if there are no components, never true for a parsed version
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// the packed pre-release label
+    pub const fn pre(&self) -> Option<PreRelease> {
+        self.pre
+    }
+
+    /// checks if the version is a pre-release, `1.2.3-rc.1`
+    pub const fn is_prerelease(&self) -> bool {
+        self.pre.is_some()
+    }
+
+    /// the numbers of the version, without the wildcard
+    pub const fn numbers(&self) -> &[u32] {
+        self.numbers.split_at(self.len).0
+    }
+
+    /// checks if the version ends in a wildcard
+    pub const fn has_wildcards(&self) -> bool {
+        self.wildcard
+    }
+
+    /// checks if the version is all numbers (no explicit wildcards)
+    pub const fn is_number(&self) -> bool {
+        !self.wildcard
+    }
+
+    /// returns true if 100% wild (all defined sections are wildcards)
+    pub const fn is_wildcard(&self) -> bool {
+        self.len == 0
+    }
+
+    /// checks compatibility between versions, the same as `Version::is_compatible_with`
+    pub const fn is_compatible_with(&self, other : &FixedVersion<N>) -> bool {
+        if self.has_wildcards() { return false; }
+        if other.is_wildcard() { return true; }
+
+        let depth = self.shared_depth(other);
+        let mut i = 0;
+        while i < depth {
+            match (self.part(i), other.part(i)) {
+                (_, None) => return true,
+                (Some(a), Some(b)) if a == b => (),
+                _ => return false,
+            }
+            i += 1;
+        }
+
+        // all the shared parts matched
+        matches!(compare_pre(self.pre, other.pre), Ordering::Equal)
+    }
+
+    /// the same ordering as `Ord`, but it can be used in a `const`. a wildcard is always
+    /// the greatest possible number when sorting
+    pub const fn compare(&self, other : &FixedVersion<N>) -> Ordering {
+        let depth = self.shared_depth(other);
+        let mut i = 0;
+        while i < depth {
+            match (self.part(i), other.part(i)) {
+                (None, None) => return Ordering::Equal,
+                (None, Some(_)) => return Ordering::Greater,
+                (Some(_), None) => return Ordering::Less,
+                (Some(a), Some(b)) => if a != b { return compare_numbers(a as u64, b as u64); },
+            }
+            i += 1;
+        }
+
+        compare_pre(self.pre, other.pre)
+    }
+
+    /// the component at the index, `None` for the wildcard
+    const fn part(&self, index : usize) -> Option<u32> {
+        if index < self.len { Some(self.numbers[index]) } else { None }
+    }
+
+    /// how many components are compared, the length of the shorter version
+    const fn shared_depth(&self, other : &FixedVersion<N>) -> usize {
+        if self.len() < other.len() { self.len() } else { other.len() }
+    }
+}
+
+/// a release is always greater than any of its pre-releases
+const fn compare_pre(pre : Option<PreRelease>, other : Option<PreRelease>) -> Ordering {
+    match (pre, other) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => a.compare(&b),
+    }
+}
+
+impl <const N : usize> PartialEq for FixedVersion<N> {
+    fn eq(&self, other : &FixedVersion<N>) -> bool {
+        //! the same as `Version`, everything after a wildcard is ignored
+        let depth = self.shared_depth(other);
+        for i in 0 .. depth {
+            match (self.part(i), other.part(i)) {
+                (Some(a), Some(b)) => if a != b { return false; },
+                _ => return true,
+            }
+        }
+        self.pre == other.pre
+    }
+}
+
+impl <const N : usize> Eq for FixedVersion<N> { }
+
+impl <const N : usize> Ord for FixedVersion<N> {
+    fn cmp(&self, other : &FixedVersion<N>) -> Ordering {
+        //! a wildcard is always the greatest possible number when sorting
+        self.compare(other)
+    }
+}
+
+impl <const N : usize> PartialOrd for FixedVersion<N> {
+    fn partial_cmp(&self, other : &FixedVersion<N>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl <const N : usize> fmt::Display for FixedVersion<N> {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        //! prints "x.x.x"
+        for (i, number) in self.numbers().iter().enumerate() {
+            if i > 0 { f.write_str(".")?; }
+            write!(f, "{}", number)?;
+        }
+        if self.wildcard {
+            f.write_str(if self.len > 0 { ".*" } else { "*" })?;
+        }
+        if let Some(pre) = self.pre {
+            write!(f, "-{}", pre)?;
+        }
+        Ok(())
+    }
+}
+
+impl <const N : usize> fmt::Debug for FixedVersion<N> {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        //! prints "FixedVersion (x.x.x)"
+        write!(f, "FixedVersion ({})", self)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl <const N : usize> FixedVersion<N> {
+
+    /// converts a `Version`, `None` if it has more than `N` numbers, a pre-release
+    /// `PreRelease` can't hold, build metadata, or a wildcard other than `*`
+    pub fn from_version(version : &Version) -> Option<FixedVersion<N>> {
+        if version.build().is_some() { return None; }
+
+        let mut fixed = FixedVersion { numbers : [0; N], len : 0, wildcard : false, pre : None };
+        if let Some(pre) = version.pre() {
+            fixed.pre = Some(PreRelease::from_str(pre)?);
+        }
+        for part in version.components() {
+            match part {
+                VersionPart::Wildcard('*') => fixed.wildcard = true,
+                VersionPart::Wildcard(_) => return None,
+                VersionPart::Number(number) => {
+                    if fixed.len >= N { return None; }
+                    fixed.numbers[fixed.len] = *number;
+                    fixed.len += 1;
+                },
+            }
+        }
+        Some(fixed)
+    }
+
+    /// converts into a `Version`
+    pub fn to_version(&self) -> Version {
+        let mut parts : Parts = self.numbers().iter().map(|n| VersionPart::Number(*n)).collect();
+        if self.wildcard {
+            parts.push(VersionPart::Wildcard('*'));
+        }
+        Version::from_parts(parts, self.pre.map(|pre| pre.to_string()), None)
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////////////////
+// TESTS GO HERE
+
+#[cfg(test)]
+mod tests {
+    use super::{FixedVersion, PreRelease};
+
+    type V = FixedVersion<4>;
+
+    #[test]
+    fn parsing() {
+        assert_eq!(V::from_str("1.2.3").unwrap().numbers(), &[1,2,3]);
+        assert_eq!(V::from_str("1.*.*").unwrap().len(), 2);
+        assert!(V::from_str("*").unwrap().is_wildcard());
+        assert!(V::from_str("1.2.3.4.5").is_none());
+        assert!(V::from_str("1.2.3-preview").is_none());
+        assert!(V::from_str("1.2.3+abc").is_none());
+        assert!(V::from_str("").is_none());
+        assert!(FixedVersion::<2>::new(&[1,2,3]).is_none());
+    }
+
+    #[test]
+    fn comparisons() {
+        assert!(V::new(&[1,2,3]) == V::from_str("1.2.3"));
+        assert!(V::new(&[1,2,3]).unwrap() < V::new(&[2,3]).unwrap());
+        assert!(V::new(&[233]).unwrap() > V::new(&[2,3]).unwrap());
+        assert!(V::new(&[22,3,56,8]).unwrap() >= V::new(&[22,3,56]).unwrap());
+        assert!(V::from_str("1.10.2").unwrap() > V::from_str("1.4.22").unwrap());
+        assert!(V::from_str("1.*").unwrap() > V::from_str("1.4.22").unwrap());
+    }
+
+    #[test]
+    fn pre_release() {
+        assert_eq!(V::from_str("1.0.0-beta.2").unwrap().pre(), Some(PreRelease::Beta(Some(2))));
+        assert!(V::from_str("1.0.0-alpha").unwrap() < V::from_str("1.0.0-alpha.1").unwrap());
+        assert!(V::from_str("1.0.0-alpha.12").unwrap() < V::from_str("1.0.0-beta").unwrap());
+        assert!(V::from_str("1.0.0-rc.1").unwrap() < V::from_str("1.0.0").unwrap());
+        assert!(V::from_str("1.0.0-rc.1").unwrap() != V::from_str("1.0.0").unwrap());
+        assert!(V::from_str("1.0.0-rc.x").is_none());
+    }
+
+    #[test]
+    fn constants() {
+        use core::cmp::Ordering;
+
+        const RUNNING : V = V::from_str("1.4.2-rc.1").unwrap();
+        const MINIMUM : V = V::from_str("1.3").unwrap();
+        const _ : () = assert!(RUNNING.compare(&MINIMUM).is_gt());
+        const _ : () = assert!(RUNNING.is_compatible_with(&V::new_wildcard()));
+
+        assert_eq!(RUNNING.pre(), Some(PreRelease::Rc(Some(1))));
+
+        // the same as `Ord`
+        let versions = ["1.2.3", "1.2", "1.*", "*", "1.2.3-alpha", "1.2.3-alpha.2", "1.2.3-beta", "1.2.3-rc.10", "2"];
+        for a in versions.iter().map(|v| V::from_str(v).unwrap()) {
+            for b in versions.iter().map(|v| V::from_str(v).unwrap()) {
+                assert_eq!(a.compare(&b), a.cmp(&b));
+            }
+        }
+        assert_eq!(PreRelease::Beta(None).compare(&PreRelease::Alpha(Some(3))), Ordering::Greater);
+    }
+
+    #[test]
+    fn is_compatible_with() {
+        let v = |s : &str| V::from_str(s).unwrap();
+        assert!(v("0.1.0").is_compatible_with(&v("0.*.*")));
+        assert!(!v("1.2.0").is_compatible_with(&v("1.1.*")));
+        assert!(!v("11.1.*").is_compatible_with(&v("11.1.4")));
+        assert!(v("1.1").is_compatible_with(&v("1.1.0")));
+        assert!(v("21.11.0").is_compatible_with(&v("*")));
+        assert!(!v("12.0").is_compatible_with(&v("12.1.2")));
+        assert!(!v("21.11").is_compatible_with(&v("12")));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn display() {
+        use crate::prelude::*;
+
+        assert_eq!(format!("{}", V::from_str("1.2.3").unwrap()), "1.2.3");
+        assert_eq!(format!("{}", V::from_str("1.*").unwrap()), "1.*");
+        assert_eq!(format!("{}", V::new_wildcard()), "*");
+        assert_eq!(format!("{}", V::from_str("1.0-beta.2").unwrap()), "1.0-beta.2");
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn conversions() {
+        use crate::Version;
+
+        let version = Version::from_str("1.2.*").unwrap();
+        assert_eq!(V::from_version(&version).unwrap().to_version().to_string(), "1.2.*");
+        assert_eq!(V::from_version(&Version::from_str("1.2.3-rc.1").unwrap()).unwrap().to_version().to_string(), "1.2.3-rc.1");
+        assert!(V::from_version(&Version::from_str("1.2.3-preview").unwrap()).is_none());
+        assert!(V::from_version(&Version::from_str("1.2.3+abc").unwrap()).is_none());
+        assert!(FixedVersion::<2>::from_version(&Version::new(&[1,2,3])).is_none());
+    }
+}
@@ -0,0 +1,192 @@
+//! **formatting** a version, with custom separators, a set number of components or
+//! zero padded numbers.
+
+use core::fmt;
+use core::fmt::Write;
+
+use crate::prelude::*;
+use crate::version::Version;
+use crate::versionpart::VersionPart;
+
+/// the rules used to render a version, `Display` uses the default rules.
+///
+/// ```
+/// # use version_lp::{Version, VersionFormatter};
+/// let version = Version::from_str("1.2-rc.1").unwrap();
+///
+/// assert_eq!(VersionFormatter::new().separator("_").format(&version), "1_2-rc.1");
+/// assert_eq!(VersionFormatter::new().components(3).zero_pad(2).format(&version), "01.02.00-rc.1");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VersionFormatter<'a> {
+    separator : &'a str,
+    pre_separator : &'a str,
+    build_separator : &'a str,
+    prefix : bool,
+    components : Option<usize>,
+    zero_pad : usize,
+}
+
+impl <'a> Default for VersionFormatter<'a> {
+    fn default() -> VersionFormatter<'a> {
+        //! renders the same as `Display`, "1.2.3-rc.1+abc"
+        VersionFormatter {
+            separator : ".",
+            pre_separator : "-",
+            build_separator : "+",
+            prefix : false,
+            components : None,
+            zero_pad : 0,
+        }
+    }
+}
+
+impl <'a> VersionFormatter<'a> {
+
+    /// creates a formatter that renders the same as `Display`
+    pub fn new() -> VersionFormatter<'a> {
+        VersionFormatter::default()
+    }
+
+    /// what goes between the components, `.` by default
+    pub fn separator(mut self, separator : &'a str) -> VersionFormatter<'a> {
+        self.separator = separator;
+        self
+    }
+
+    /// what goes before the pre-release label, `-` by default
+    pub fn pre_separator(mut self, separator : &'a str) -> VersionFormatter<'a> {
+        self.pre_separator = separator;
+        self
+    }
+
+    /// what goes before the build metadata, `+` by default
+    pub fn build_separator(mut self, separator : &'a str) -> VersionFormatter<'a> {
+        self.build_separator = separator;
+        self
+    }
+
+    /// writes the prefix before the version, the one it was parsed with or `v`
+    pub fn prefix(mut self, prefix : bool) -> VersionFormatter<'a> {
+        self.prefix = prefix;
+        self
+    }
+
+    /// writes exactly this many components (at least 1), dropping the extra ones or adding `0`s.
+    /// nothing is added after a wildcard.
+    pub fn components(mut self, components : usize) -> VersionFormatter<'a> {
+        self.components = Some(components.max(1));
+        self
+    }
+
+    /// pads every number with zeros to this many digits, `1.2.3` is `01.02.03` with 2
+    pub fn zero_pad(mut self, digits : usize) -> VersionFormatter<'a> {
+        self.zero_pad = digits;
+        self
+    }
+
+    /// renders the version as a string
+    pub fn format(&self, version : &Version) -> String {
+        let mut rendered = String::new();
+        // writing to a `String` never fails
+        let _ = self.write(version, &mut rendered);
+        rendered
+    }
+
+    /// writes the version to anything that implements `fmt::Write`, without making a `String`
+    pub fn write<W : Write>(&self, version : &Version, out : &mut W) -> fmt::Result {
+        if self.prefix {
+            out.write_char(version.prefix().unwrap_or('v'))?;
+        }
+
+        let parts = version.components();
+        let count = self.components.unwrap_or(parts.len());
+        for i in 0 .. count {
+            let part = match parts.get(i) {
+                Some(part) => *part,
+                // can't add numbers after a wildcard
+                None if parts.last().is_some_and(VersionPart::is_wildcard) => break,
+                None => VersionPart::Number(0),
+            };
+
+            if i > 0 { out.write_str(self.separator)?; }
+            match part {
+                VersionPart::Number(number) => write!(out, "{:01$}", number, self.zero_pad)?,
+                VersionPart::Wildcard(wildcard) => out.write_char(wildcard)?,
+            }
+        }
+
+        if let Some(pre) = version.pre() {
+            out.write_str(self.pre_separator)?;
+            out.write_str(pre)?;
+        }
+        if let Some(build) = version.build() {
+            out.write_str(self.build_separator)?;
+            out.write_str(build)?;
+        }
+        Ok(())
+    }
+}
+
+/// writes the version using the flags of the format spec, see `Display for Version`
+pub(crate) fn display(version : &Version, f : &mut fmt::Formatter) -> fmt::Result {
+    let mut formatter = VersionFormatter::new().prefix(f.alternate());
+    if let Some(components) = f.precision() {
+        formatter = formatter.components(components);
+    }
+
+    match f.width() {
+        Some(width) if f.sign_aware_zero_pad() => formatter.zero_pad(width).write(version, f),
+        Some(width) => {
+            // the padding is done by hand, `Formatter::pad` would cut the string to the precision
+            let rendered = formatter.format(version);
+            let padding = width.saturating_sub(rendered.chars().count());
+            let (before, after) = match f.align() {
+                Some(fmt::Alignment::Right) => (padding, 0),
+                Some(fmt::Alignment::Center) => (padding / 2, padding - padding / 2),
+                _ => (0, padding),
+            };
+
+            for _ in 0 .. before { f.write_char(f.fill())?; }
+            f.write_str(&rendered)?;
+            for _ in 0 .. after { f.write_char(f.fill())?; }
+            Ok(())
+        },
+        None => formatter.write(version, f),
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////////////////
+// TESTS GO HERE
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_flags() {
+        let version = Version::from_str("1.2.3-rc.1+abc").unwrap();
+
+        assert_eq!(format!("{}", version), "1.2.3-rc.1+abc");
+        assert_eq!(format!("{:#}", version), "v1.2.3-rc.1+abc");
+        assert_eq!(format!("{:.2}", version), "1.2-rc.1+abc");
+        assert_eq!(format!("{:.4}", version), "1.2.3.0-rc.1+abc");
+        assert_eq!(format!("{:03}", version), "001.002.003-rc.1+abc");
+        assert_eq!(format!("{:>16}", Version::new(&[1,2])), "             1.2");
+        assert_eq!(format!("{:*^7.2}", Version::new(&[1,2,3])), "**1.2**");
+        assert_eq!(format!("{:.4}", Version::from_str("1.*").unwrap()), "1.*");
+
+        let prefixed = crate::VersionParser::new().allow_prefix(true).parse("V2.0").unwrap();
+        assert_eq!(format!("{:#}", prefixed), "V2.0");
+    }
+
+    #[test]
+    fn formatter() {
+        let version = Version::from_str("1.2.3-rc.1+abc").unwrap();
+
+        let formatter = VersionFormatter::new().separator("_").pre_separator("~").build_separator("_build_");
+        assert_eq!(formatter.format(&version), "1_2_3~rc.1_build_abc");
+        assert_eq!(VersionFormatter::new().components(1).format(&version), "1-rc.1+abc");
+        assert_eq!(VersionFormatter::new().prefix(true).format(&Version::new(&[3])), "v3");
+    }
+}
@@ -0,0 +1,140 @@
+//! the dot separated **identifiers** used in pre-release labels and build metadata, `rc.1`
+//!
+//! these are all `const fn`s so versions can be parsed and compared while compiling,
+//! and they don't need an allocator so `FixedVersion` can use them too.
+
+use core::cmp::Ordering;
+
+/// checks that the section is only made of digits, `u32::from_str` also accepts a `+`
+pub(crate) const fn is_numeric(section : &str) -> bool {
+    is_digits(section.as_bytes())
+}
+
+/// the index of the first `byte` between `start` and `end`, or `end`
+pub(crate) const fn find(bytes : &[u8], byte : u8, start : usize, end : usize) -> usize {
+    let mut i = start;
+    while i < end && bytes[i] != byte { i += 1; }
+    i
+}
+
+/// parses a section that is already known to be digits, `None` if it is too large.
+/// `u32::from_str` isn't a `const fn`
+pub(crate) const fn parse_number(section : &str) -> Option<u32> {
+    let digits = section.as_bytes();
+    let mut number : u32 = 0;
+
+    let mut i = 0;
+    while i < digits.len() {
+        number = match number.checked_mul(10) {
+            Some(number) => match number.checked_add((digits[i] - b'0') as u32) {
+                Some(number) => number,
+                None => return None,
+            },
+            None => return None,
+        };
+        i += 1;
+    }
+    Some(number)
+}
+
+/// labels are dot separated, non empty, alphanumeric (and `-`) identifiers
+pub(crate) const fn is_valid_label(label : &str) -> bool {
+    let label = label.as_bytes();
+    let mut identifier_len = 0;
+
+    let mut i = 0;
+    while i < label.len() {
+        match label[i] {
+            b'.' if identifier_len == 0 => return false,
+            b'.' => identifier_len = 0,
+            b'-' => identifier_len += 1,
+            b if b.is_ascii_alphanumeric() => identifier_len += 1,
+            _ => return false,
+        }
+        i += 1;
+    }
+
+    identifier_len > 0
+}
+
+/// compares labels identifier by identifier, numbers are sorted numerically and
+/// below text, and a label is greater than any label it starts with.
+pub(crate) const fn compare_labels(a : &str, b : &str, ignore_case : bool) -> Ordering {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let (mut a_start, mut b_start) = (0, 0);
+
+    loop {
+        // starting past the end means there are no identifiers left
+        match (a_start > a.len(), b_start > b.len()) {
+            (true, true) => return Ordering::Equal,
+            (true, false) => return Ordering::Less,
+            (false, true) => return Ordering::Greater,
+            (false, false) => (),
+        }
+
+        let a_end = identifier_end(a, a_start);
+        let b_end = identifier_end(b, b_start);
+        let a_id = a.split_at(a_end).0.split_at(a_start).1;
+        let b_id = b.split_at(b_end).0.split_at(b_start).1;
+
+        let ordering = match (is_digits(a_id), is_digits(b_id)) {
+            (true, true) => {
+                let (a_id, b_id) = (trim_zeros(a_id), trim_zeros(b_id));
+                match compare_numbers(a_id.len() as u64, b_id.len() as u64) {
+                    Ordering::Equal => compare_bytes(a_id, b_id, false),
+                    ordering => ordering,
+                }
+            },
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            (false, false) => compare_bytes(a_id, b_id, ignore_case),
+        };
+        if !matches!(ordering, Ordering::Equal) { return ordering; }
+
+        a_start = a_end + 1;
+        b_start = b_end + 1;
+    }
+}
+
+/// the index of the `.` after the identifier that starts at `start`, or the length
+const fn identifier_end(label : &[u8], start : usize) -> usize {
+    let mut end = start;
+    while end < label.len() && label[end] != b'.' { end += 1; }
+    end
+}
+
+const fn is_digits(section : &[u8]) -> bool {
+    if section.is_empty() { return false; }
+
+    let mut i = 0;
+    while i < section.len() {
+        if !section[i].is_ascii_digit() { return false; }
+        i += 1;
+    }
+    true
+}
+
+const fn trim_zeros(number : &[u8]) -> &[u8] {
+    let mut start = 0;
+    while start < number.len() && number[start] == b'0' { start += 1; }
+    number.split_at(start).1
+}
+
+/// compares byte by byte, a shorter string that the other starts with is less
+const fn compare_bytes(a : &[u8], b : &[u8], ignore_case : bool) -> Ordering {
+    let mut i = 0;
+    while i < a.len() && i < b.len() {
+        let (a_byte, b_byte) = match ignore_case {
+            true => (a[i].to_ascii_lowercase(), b[i].to_ascii_lowercase()),
+            false => (a[i], b[i]),
+        };
+        if a_byte != b_byte { return compare_numbers(a_byte as u64, b_byte as u64); }
+        i += 1;
+    }
+    compare_numbers(a.len() as u64, b.len() as u64)
+}
+
+/// `Ord::cmp` isn't a `const fn`
+pub(crate) const fn compare_numbers(a : u64, b : u64) -> Ordering {
+    if a < b { Ordering::Less } else if a > b { Ordering::Greater } else { Ordering::Equal }
+}
@@ -0,0 +1,235 @@
+//! a sorted **index** of versions, for answering requirement queries on big lists without
+//! scanning them.
+
+use core::cmp::Ordering;
+use core::iter::FromIterator;
+use core::ops::Range;
+
+use crate::prelude::*;
+use crate::version::Version;
+use crate::versionpart::VersionPart;
+use crate::identifiers::compare_labels;
+
+/// a list of versions that is kept sorted, so matching a requirement is a few binary
+/// searches instead of checking every version.
+///
+/// the order is the same as `Version`, except a shorter version is placed before the longer
+/// versions it starts, so `1.2` comes before `1.2.0`. versions with wildcards can't be added.
+///
+/// ```
+/// # use version_lp::{Version, VersionIndex};
+/// let index : VersionIndex = ["1.0.0", "1.2.0", "1.2.5", "2.0.0"].iter()
+///     .filter_map(|v| Version::from_str(v))
+///     .collect();
+///
+/// let requirement = Version::from_str("1.*").unwrap();
+/// assert_eq!(index.latest_matching(&requirement), Some(&Version::new(&[1,2,5])));
+/// assert_eq!(index.all_matching(&requirement).count(), 3);
+/// assert_eq!(index.next_after(&Version::new(&[1,2,5])), Some(&Version::new(&[2,0,0])));
+/// ```
+#[derive(Clone, Default)]
+pub struct VersionIndex {
+    versions : Vec<Version>,
+}
+
+impl VersionIndex {
+
+    /// creates an empty index
+    pub fn new() -> VersionIndex {
+        VersionIndex { versions : Vec::new() }
+    }
+
+    /// adds a version to the index, returns `false` if it has wildcards or is already
+    /// in the index (build metadata isn't used when checking)
+    pub fn insert(&mut self, version : Version) -> bool {
+        if version.has_wildcards() { return false; }
+
+        match self.versions.binary_search_by(|v| index_cmp(v, &version)) {
+            Ok(_) => false,
+            Err(i) => {
+                self.versions.insert(i, version);
+                true
+            }
+        }
+    }
+
+    /// removes the version from the index, returning it if it was there
+    pub fn remove(&mut self, version : &Version) -> Option<Version> {
+        match self.versions.binary_search_by(|v| index_cmp(v, version)) {
+            Ok(i) => Some(self.versions.remove(i)),
+            Err(_) => None,
+        }
+    }
+
+    /// the number of versions in the index
+    pub fn len(&self) -> usize {
+        self.versions.len()
+    }
+
+    /// checks if there are no versions in the index
+    pub fn is_empty(&self) -> bool {
+        self.versions.is_empty()
+    }
+
+    /// all the versions, from the smallest to the largest
+    pub fn iter(&self) -> core::slice::Iter<'_, Version> {
+        self.versions.iter()
+    }
+
+    /// the largest version that is compatible with the requirement, the same as checking
+    /// `is_compatible_with` on every version
+    pub fn latest_matching(&self, requirement : &Version) -> Option<&Version> {
+        self.all_matching(requirement).next_back()
+    }
+
+    /// all the versions that are compatible with the requirement, from the smallest to the largest
+    pub fn all_matching<'a>(&'a self, requirement : &Version) -> impl DoubleEndedIterator<Item = &'a Version> + 'a {
+        // the numbers before the wildcard, the parser doesn't allow anything after one
+        let numbers = &requirement.components()[.. requirement.components().iter().take_while(|p| p.is_number()).count()];
+        let wild = requirement.has_wildcards();
+
+        // versions shorter than the requirement only match if they start it, `1.2` matches `1.2.0`,
+        // everything else that matches starts with the numbers of the requirement
+        let mut ranges : Vec<Range<usize>> = Vec::with_capacity(numbers.len());
+        for depth in 1 .. numbers.len() {
+            let start = self.versions.partition_point(|v| v.components() < &numbers[.. depth]);
+            let end = self.versions.partition_point(|v| v.components() <= &numbers[.. depth]);
+            ranges.push(start .. end);
+        }
+        ranges.push(self.prefix_range(numbers));
+
+        // past a wildcard the pre-release doesn't matter, otherwise it has to be the same
+        let depth = numbers.len();
+        let pre = requirement.pre().map(String::from);
+        ranges.into_iter()
+            .flat_map(move |range| self.versions[range].iter())
+            .filter(move |v| (wild && v.components().len() > depth) || v.pre() == pre.as_deref())
+    }
+
+    /// the smallest version that comes after the version
+    pub fn next_after(&self, version : &Version) -> Option<&Version> {
+        let i = self.versions.partition_point(|v| index_cmp(v, version) != Ordering::Greater);
+        self.versions.get(i)
+    }
+
+    /// the largest version that comes before the version
+    pub fn previous_before(&self, version : &Version) -> Option<&Version> {
+        let i = self.versions.partition_point(|v| index_cmp(v, version) == Ordering::Less);
+        if i > 0 { self.versions.get(i - 1) } else { None }
+    }
+
+    /// the range of versions that start with all the numbers
+    fn prefix_range(&self, numbers : &[VersionPart]) -> Range<usize> {
+        let prefix_cmp = |v : &Version| {
+            let parts = v.components();
+            let depth = parts.len().min(numbers.len());
+            parts[.. depth].cmp(&numbers[.. depth])
+                .then(if parts.len() < numbers.len() { Ordering::Less } else { Ordering::Equal })
+        };
+
+        let start = self.versions.partition_point(|v| prefix_cmp(v) == Ordering::Less);
+        let end = self.versions.partition_point(|v| prefix_cmp(v) != Ordering::Greater);
+        start .. end
+    }
+}
+
+impl FromIterator<Version> for VersionIndex {
+    fn from_iter<I : IntoIterator<Item = Version>>(iter : I) -> VersionIndex {
+        //! sorts once, instead of inserting one at a time
+        let mut versions : Vec<Version> = iter.into_iter().filter(|v| !v.has_wildcards()).collect();
+        versions.sort_by(index_cmp);
+        versions.dedup_by(|a, b| index_cmp(a, b) == Ordering::Equal);
+        VersionIndex { versions }
+    }
+}
+
+impl Extend<Version> for VersionIndex {
+    fn extend<I : IntoIterator<Item = Version>>(&mut self, iter : I) {
+        for version in iter {
+            self.insert(version);
+        }
+    }
+}
+
+impl <'a> IntoIterator for &'a VersionIndex {
+    type Item = &'a Version;
+    type IntoIter = core::slice::Iter<'a, Version>;
+
+    fn into_iter(self) -> core::slice::Iter<'a, Version> {
+        self.iter()
+    }
+}
+
+/// the order of the index, the same as `Version` but with every component compared,
+/// so it is a total order
+pub(crate) fn index_cmp(a : &Version, b : &Version) -> Ordering {
+    a.components().cmp(b.components()).then_with(|| match (a.pre(), b.pre()) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => compare_labels(a, b, false),
+    })
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////////////////
+// TESTS GO HERE
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIST : [&str; 14] = [
+        "1.0.0", "1.2.0-rc.1", "1.2.0", "1.2", "1.2.0.1", "1.2.5", "1.10.0", "2.0.0-beta",
+        "2.0.0", "0.9", "1", "3.1.4", "1.2.5-rc.2", "2.0",
+    ];
+
+    fn index() -> VersionIndex {
+        LIST.iter().filter_map(|v| Version::from_str(v)).collect()
+    }
+
+    #[test]
+    fn matches_the_same_as_a_scan() {
+        let index = index();
+        let requirements = ["*", "1.*", "1.2.*", "1.2", "1.2.0", "1.2.5-rc.2", "2", "2.0.0-beta", "4.*", "1.2.0.1.*"];
+
+        for requirement in requirements.iter() {
+            let requirement = Version::from_str(requirement).unwrap();
+
+            let mut scanned : Vec<&Version> = index.iter().filter(|v| v.is_compatible_with(&requirement)).collect();
+            scanned.sort_by(|a, b| index_cmp(a, b));
+            let found : Vec<&Version> = index.all_matching(&requirement).collect();
+
+            let render = |list : &[&Version]| list.iter().map(|v| Version::to_string(v)).collect::<Vec<String>>();
+            assert_eq!(render(&found), render(&scanned), "{}", requirement.to_string());
+            assert_eq!(index.latest_matching(&requirement).map(Version::to_string), scanned.last().map(|v| Version::to_string(v)));
+        }
+    }
+
+    #[test]
+    fn neighbours() {
+        let index = index();
+        let v = |s : &str| Version::from_str(s).unwrap();
+
+        assert_eq!(index.next_after(&v("1.2.0")).unwrap().to_string(), "1.2.0.1");
+        assert_eq!(index.next_after(&v("1.3")).unwrap().to_string(), "1.10.0");
+        assert_eq!(index.previous_before(&v("2.0.0")).unwrap().to_string(), "2.0.0-beta");
+        assert!(index.previous_before(&v("0.9")).is_none());
+        assert!(index.next_after(&v("3.1.4")).is_none());
+    }
+
+    #[test]
+    fn insert_and_remove() {
+        let mut index = index();
+        let len = index.len();
+
+        assert!(!index.insert(Version::from_str("1.2.0").unwrap()));
+        assert!(!index.insert(Version::from_str("1.*").unwrap()));
+        assert!(index.insert(Version::from_str("1.2.7").unwrap()));
+        assert_eq!(index.latest_matching(&Version::from_str("1.2.*").unwrap()).unwrap().to_string(), "1.2.7");
+
+        assert!(index.remove(&Version::from_str("1.2.7").unwrap()).is_some());
+        assert!(index.remove(&Version::from_str("1.2.7").unwrap()).is_none());
+        assert_eq!(index.len(), len);
+        assert!(index.iter().zip(index.iter().skip(1)).all(|(a, b)| index_cmp(a, b) == Ordering::Less));
+    }
+}
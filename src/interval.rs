@@ -0,0 +1,242 @@
+//! an **interval** map, values for ranges of versions that don't overlap.
+
+use core::fmt;
+use core::cmp::Ordering;
+use core::iter::FromIterator;
+use core::ops::Range;
+
+use crate::prelude::*;
+use crate::version::Version;
+use crate::index::index_cmp;
+
+/// values for ranges of versions, like the protocol each range of releases uses.
+///
+/// the ranges are half open and never overlap, adding a range replaces the part of any range
+/// it covers and ranges next to each other with the same value are merged.
+///
+/// versions are ordered the same as `VersionIndex`: like `Version`, but with every component
+/// compared so a shorter version comes before the longer ones it starts. `1.0 .. 1.5` covers
+/// everything up to `1.4.x`, and `1.5 .. 3` starts at `1.5` and covers `1.5.0` through `2.x`.
+///
+/// ```
+/// # use version_lp::{Version, IntervalVersionMap};
+/// let v = |s| Version::from_str(s).unwrap();
+///
+/// let mut protocols = IntervalVersionMap::new();
+/// protocols.insert(v("1.0") .. v("1.5"), 'A');
+/// protocols.insert(v("1.5") .. v("3"), 'B');
+///
+/// assert_eq!(protocols.get(&v("1.4.2")), Some(&'A'));
+/// assert_eq!(protocols.get(&v("2.9.1")), Some(&'B'));
+/// assert_eq!(protocols.get(&v("3.0.0")), None);
+/// ```
+#[derive(Clone)]
+pub struct IntervalVersionMap<T> {
+    /// sorted by the start of the range
+    entries : Vec<(Range<Version>, T)>,
+}
+
+impl <T> Default for IntervalVersionMap<T> {
+    fn default() -> IntervalVersionMap<T> {
+        IntervalVersionMap { entries : Vec::new() }
+    }
+}
+
+impl <T : fmt::Debug> fmt::Debug for IntervalVersionMap<T> {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        //! prints "{x.x..x.x: value, ..}"
+        f.debug_map().entries(self.iter().map(|(range, value)| (format!("{}..{}", range.start, range.end), value))).finish()
+    }
+}
+
+impl <T> IntervalVersionMap<T> {
+
+    /// creates an empty map
+    pub fn new() -> IntervalVersionMap<T> {
+        IntervalVersionMap::default()
+    }
+
+    /// the value of the range that has the version in it
+    pub fn get(&self, version : &Version) -> Option<&T> {
+        self.get_key_value(version).map(|(_, value)| value)
+    }
+
+    /// the range that has the version in it, and its value
+    pub fn get_key_value(&self, version : &Version) -> Option<(&Range<Version>, &T)> {
+        let i = self.entries.partition_point(|(range, _)| index_cmp(&range.start, version) != Ordering::Greater);
+        let (range, value) = self.entries.get(i.checked_sub(1)?)?;
+
+        if index_cmp(version, &range.end) == Ordering::Less { Some((range, value)) } else { None }
+    }
+
+    /// all the ranges and their values, from the smallest versions
+    pub fn iter(&self) -> impl Iterator<Item = (&Range<Version>, &T)> {
+        self.entries.iter().map(|(range, value)| (range, value))
+    }
+
+    /// the ranges that overlap the range and their values, from the smallest versions.
+    /// the ranges aren't cut to fit
+    pub fn range<'a>(&'a self, range : &'a Range<Version>) -> impl Iterator<Item = (&'a Range<Version>, &'a T)> + 'a {
+        let start = self.entries.partition_point(|(r, _)| index_cmp(&r.end, &range.start) != Ordering::Greater);
+        self.entries[start ..].iter()
+            .take_while(move |(r, _)| index_cmp(&r.start, &range.end) == Ordering::Less)
+            .map(|(range, value)| (range, value))
+    }
+
+    /// the parts of the range that aren't in the map
+    pub fn gaps(&self, range : &Range<Version>) -> Vec<Range<Version>> {
+        let mut gaps = Vec::new();
+        let mut start = &range.start;
+
+        for (covered, _) in self.range(range) {
+            if index_cmp(start, &covered.start) == Ordering::Less {
+                gaps.push(start.clone() .. covered.start.clone());
+            }
+            start = &covered.end;
+        }
+        if index_cmp(start, &range.end) == Ordering::Less {
+            gaps.push(start.clone() .. range.end.clone());
+        }
+        gaps
+    }
+
+    /// the number of ranges in the map
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// checks if there are no ranges in the map
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl <T : Clone + PartialEq> IntervalVersionMap<T> {
+
+    /// sets the value for the range, the ranges it overlaps are cut down (or split in two)
+    /// and it is merged with the ranges next to it that have the same value. empty ranges
+    /// aren't added
+    pub fn insert(&mut self, range : Range<Version>, value : T) {
+        if index_cmp(&range.start, &range.end) != Ordering::Less { return; }
+
+        self.cut(&range);
+        let i = self.entries.partition_point(|(r, _)| index_cmp(&r.start, &range.start) == Ordering::Less);
+        self.entries.insert(i, (range, value));
+
+        // merging with the next range, and then the previous one
+        if i + 1 < self.entries.len() { self.merge(i); }
+        if i > 0 { self.merge(i - 1); }
+    }
+
+    /// removes the range from the map, cutting down (or splitting) the ranges it overlaps
+    pub fn remove(&mut self, range : &Range<Version>) {
+        if index_cmp(&range.start, &range.end) != Ordering::Less { return; }
+        self.cut(range);
+    }
+
+    /// takes the range out of the ranges in the map
+    fn cut(&mut self, range : &Range<Version>) {
+        let start = self.entries.partition_point(|(r, _)| index_cmp(&r.end, &range.start) != Ordering::Greater);
+        let end = self.entries.partition_point(|(r, _)| index_cmp(&r.start, &range.end) == Ordering::Less);
+
+        let mut kept = Vec::new();
+        for (r, value) in self.entries.drain(start .. end) {
+            if index_cmp(&r.start, &range.start) == Ordering::Less {
+                kept.push((r.start.clone() .. range.start.clone(), value.clone()));
+            }
+            if index_cmp(&range.end, &r.end) == Ordering::Less {
+                kept.push((range.end.clone() .. r.end, value));
+            }
+        }
+        self.entries.splice(start .. start, kept);
+    }
+
+    /// merges the range at the index with the next one, if they touch and have the same value
+    fn merge(&mut self, i : usize) {
+        let (current, next) = (&self.entries[i], &self.entries[i + 1]);
+        if index_cmp(&current.0.end, &next.0.start) == Ordering::Equal && current.1 == next.1 {
+            let (next, _) = self.entries.remove(i + 1);
+            self.entries[i].0.end = next.end;
+        }
+    }
+}
+
+impl <T : Clone + PartialEq> FromIterator<(Range<Version>, T)> for IntervalVersionMap<T> {
+    fn from_iter<I : IntoIterator<Item = (Range<Version>, T)>>(iter : I) -> IntervalVersionMap<T> {
+        //! the ranges are added in order, so later ones replace the earlier ones they overlap
+        let mut map = IntervalVersionMap::new();
+        for (range, value) in iter {
+            map.insert(range, value);
+        }
+        map
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////////////////
+// TESTS GO HERE
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(version : &str) -> Version {
+        Version::from_str(version).unwrap()
+    }
+
+    fn rendered(map : &IntervalVersionMap<char>) -> Vec<String> {
+        map.iter().map(|(range, value)| format!("{}..{} {}", range.start, range.end, value)).collect()
+    }
+
+    #[test]
+    fn insert() {
+        let mut map = IntervalVersionMap::new();
+        map.insert(v("1.0") .. v("2.0"), 'A');
+        map.insert(v("1.2") .. v("1.4"), 'B');
+        assert_eq!(rendered(&map), vec!["1.0..1.2 A", "1.2..1.4 B", "1.4..2.0 A"]);
+
+        // covering a range replaces it, and touching ranges with the same value are merged
+        map.insert(v("1.1") .. v("1.5"), 'A');
+        assert_eq!(rendered(&map), vec!["1.0..2.0 A"]);
+        map.insert(v("2.0") .. v("3"), 'A');
+        map.insert(v("0.5") .. v("1.0"), 'A');
+        assert_eq!(rendered(&map), vec!["0.5..3 A"]);
+
+        map.insert(v("2.5") .. v("4"), 'C');
+        map.insert(v("3") .. v("3"), 'D');
+        assert_eq!(rendered(&map), vec!["0.5..2.5 A", "2.5..4 C"]);
+
+        map.remove(&(v("1") .. v("3.2")));
+        assert_eq!(rendered(&map), vec!["0.5..1 A", "3.2..4 C"]);
+        assert_eq!(map.len(), 2);
+    }
+
+    #[test]
+    fn lookup() {
+        let map : IntervalVersionMap<char> = vec![
+            (v("1.0") .. v("1.5"), 'A'),
+            (v("1.5") .. v("3"), 'B'),
+            (v("4.0.0-rc.1") .. v("4.1"), 'C'),
+        ].into_iter().collect();
+
+        assert_eq!(map.get(&v("0.9")), None);
+        assert_eq!(map.get(&v("1.0")), Some(&'A'));
+        assert_eq!(map.get(&v("1.4.9")), Some(&'A'));
+        assert_eq!(map.get(&v("1.5")), Some(&'B'));
+        assert_eq!(map.get(&v("1.5.0")), Some(&'B'));
+        assert_eq!(map.get(&v("2.99.1")), Some(&'B'));
+        assert_eq!(map.get(&v("3")), None);
+        assert_eq!(map.get(&v("4.0.0-beta")), None);
+        assert_eq!(map.get(&v("4.0.0-rc.2")), Some(&'C'));
+        assert_eq!(map.get(&v("4.0.7")), Some(&'C'));
+
+        let overlapping : Vec<char> = map.range(&(v("1.4") .. v("3.5"))).map(|(_, value)| *value).collect();
+        assert_eq!(overlapping, vec!['A', 'B']);
+        assert_eq!(map.range(&(v("3") .. v("4"))).count(), 0);
+
+        let gaps : Vec<String> = map.gaps(&(v("0.1") .. v("5"))).iter().map(|r| format!("{}..{}", r.start, r.end)).collect();
+        assert_eq!(gaps, vec!["0.1..1.0", "3..4.0.0-rc.1", "4.1..5"]);
+        assert!(map.gaps(&(v("1.2") .. v("2"))).is_empty());
+
+        assert_eq!(format!("{:?}", map), r#"{"1.0..1.5": 'A', "1.5..3": 'B', "4.0.0-rc.1..4.1": 'C'}"#);
+    }
+}
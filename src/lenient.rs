@@ -0,0 +1,288 @@
+//! best effort parsing of messy version strings, like the ones found on release pages.
+//!
+//! strips the text around the version (`v1.2`, `release-2.3.4`, `1.2.3.Final`), guesses
+//! what kind of version it was and how sure it is about it.
+
+use core::fmt;
+use core::str::FromStr;
+
+use crate::prelude::*;
+use crate::version::Version;
+
+/// prefixes that are commonly put before a version, all lowercase
+const KNOWN_PREFIXES : [&str; 8] = [ "v", "version", "release", "rel", "ver", "tag", "stable", "build" ];
+
+/// prefixes used for single number revisions, `r1234`, `svn1234` etc..
+const REVISION_PREFIXES : [&str; 4] = [ "r", "rev", "revision", "svn" ];
+
+/// suffixes that only mark a version as a final release, all lowercase
+const KNOWN_SUFFIXES : [&str; 6] = [ "final", "release", "ga", "stable", "rtm", "lts" ];
+
+/// the kind of version that was found in the string
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum DetectedScheme {
+    /// numbers split by `.` (or `_`), `1.2.3`
+    Dotted,
+    /// a calendar date, `2024-05-01` or `20240501`
+    Date,
+    /// a single increasing number, `r1234`
+    Revision,
+}
+
+/// why a version couldn't be found in the string
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LenientError {
+    /// there aren't any numbers in the string
+    NoNumbers,
+    /// the number is too large for a component
+    TooLarge(String),
+}
+
+impl fmt::Display for LenientError {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LenientError::NoNumbers => write!(f, "there aren't any numbers to make a version out of"),
+            LenientError::TooLarge(number) => write!(f, "{} is too large for a version component", number),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LenientError { }
+
+/// the result of a lenient parse, the version with what was removed to get to it.
+pub struct LenientVersion {
+    version : Version,
+    scheme : DetectedScheme,
+    confidence : f32,
+    prefix : Option<String>,
+    suffix : Option<String>,
+}
+
+impl LenientVersion {
+
+    /// finds the version inside of the string.
+    ///
+    /// returns `None` if there aren't any numbers to make a version out of, or they are too
+    /// large. `str::parse` gives a `LenientError` saying which
+    // kept next to `FromStr` for the `Option` api, like `Version::from_str`
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(version : &str) -> Option<LenientVersion> {
+        version.parse().ok()
+    }
+
+    /// the normalized version
+    pub fn version(&self) -> &Version { &self.version }
+
+    /// takes the version out of the result
+    pub fn into_version(self) -> Version { self.version }
+
+    /// what kind of version the string looked like
+    pub fn scheme(&self) -> DetectedScheme { self.scheme }
+
+    /// how sure the guess is, from `0.0` to `1.0`. a clean `1.2.3` is `1.0`
+    pub fn confidence(&self) -> f32 { self.confidence }
+
+    /// the text that was removed from the front of the string (not counting whitespace)
+    pub fn stripped_prefix(&self) -> Option<&str> { self.prefix.as_deref() }
+
+    /// the text that was removed from the end of the string (not counting whitespace)
+    pub fn stripped_suffix(&self) -> Option<&str> { self.suffix.as_deref() }
+
+    /// checks if anything other than whitespace was removed
+    pub fn was_stripped(&self) -> bool { self.prefix.is_some() || self.suffix.is_some() }
+}
+
+impl FromStr for LenientVersion {
+    type Err = LenientError;
+
+    fn from_str(version : &str) -> Result<LenientVersion, LenientError> {
+        //! finds the version inside of the string, see `LenientVersion::from_str`
+        let trimmed = version.trim();
+
+        let start = trimmed.find(|c : char| c.is_ascii_digit()).ok_or(LenientError::NoNumbers)?;
+        let (prefix, rest) = trimmed.split_at(start);
+        let (core, separator) = take_core(rest);
+        let suffix = &rest[core.len()..];
+
+        // the core is only digits and the separator, so a number that doesn't parse is too large
+        let number = |n : &str| n.parse::<u32>().map_err(|_| LenientError::TooLarge(n.to_string()));
+        let numbers : Vec<u32> = match separator {
+            Some(separator) => core.split(separator).map(number).collect::<Result<_, _>>()?,
+            None => vec![number(core)?],
+        };
+
+        let prefix_word = prefix.trim_end_matches(['-', '_', '.', ' ']).to_ascii_lowercase();
+        let suffix_word = suffix.trim_start_matches(['-', '_', '.', ' ']).to_ascii_lowercase();
+
+        let (scheme, mut confidence) = if is_date(core, separator, &numbers) {
+            (DetectedScheme::Date, 0.9)
+        } else if numbers.len() == 1 && REVISION_PREFIXES.contains(&prefix_word.as_str()) {
+            (DetectedScheme::Revision, 0.9)
+        } else {
+            match separator {
+                Some('.') => (DetectedScheme::Dotted, 1.0),
+                Some(_) => (DetectedScheme::Dotted, 0.8),
+                None => (DetectedScheme::Dotted, 0.6),
+            }
+        };
+
+        // the more text that had to be removed, the less sure we are.
+        if scheme != DetectedScheme::Revision && !prefix_word.is_empty() {
+            confidence -= if KNOWN_PREFIXES.contains(&prefix_word.as_str()) { 0.05 } else { 0.3 };
+        }
+        if !suffix_word.is_empty() {
+            confidence -= if KNOWN_SUFFIXES.contains(&suffix_word.as_str()) { 0.1 } else { 0.3 };
+        }
+
+        let confidence : f32 = if confidence < 0.0 { 0.0 } else { confidence };
+
+        Ok(LenientVersion {
+            version : Version::new(&numbers),
+            scheme,
+            confidence,
+            prefix : if prefix.is_empty() { None } else { Some(prefix.to_string()) },
+            suffix : if suffix.is_empty() { None } else { Some(suffix.to_string()) },
+        })
+    }
+}
+
+impl fmt::Debug for LenientVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "LenientVersion ({}, {:?}, {})", self.version.to_string(), self.scheme, self.confidence)
+    }
+}
+
+/// takes the version numbers from the front of the string.
+///
+/// the numbers are split by the first separator found (`.`, `_` or `-`), a
+/// separator is only taken if there is a number after it.
+fn take_core(string : &str) -> (&str, Option<char>) {
+    let bytes = string.as_bytes();
+    let mut separator : Option<char> = None;
+    let mut end = 0;
+
+    while end < bytes.len() && bytes[end].is_ascii_digit() { end += 1; }
+
+    loop {
+        if end + 1 >= bytes.len() { break; }
+        let next = bytes[end] as char;
+        if !bytes[end + 1].is_ascii_digit() { break; }
+        match separator {
+            None if next == '.' || next == '_' || next == '-' => separator = Some(next),
+            Some(s) if s == next => { },
+            _ => break,
+        }
+        end += 1;
+        while end < bytes.len() && bytes[end].is_ascii_digit() { end += 1; }
+    }
+
+    (&string[..end], separator)
+}
+
+/// checks if the numbers look like a `yyyy-mm-dd` or `yyyymmdd` date
+fn is_date(core : &str, separator : Option<char>, numbers : &[u32]) -> bool {
+    let valid = |year : u32, month : u32, day : u32| {
+        (1970 ..= 2100).contains(&year) && (1 ..= 12).contains(&month) && (1 ..= 31).contains(&day)
+    };
+
+    match (separator, numbers) {
+        (None, &[number]) if core.len() == 8 => valid(number / 10000, number / 100 % 100, number % 100),
+        (Some(_), &[year, month, day]) => core.find(|c : char| !c.is_ascii_digit()) == Some(4) && valid(year, month, day),
+        _ => false,
+    }
+}
+
+impl Version {
+    /// creates a version from a messy string, ignoring any text around it.
+    ///
+    /// a shortcut for `LenientVersion::from_str`, when only the version is needed.
+    pub fn from_str_lenient(version : &str) -> Option<Version> {
+        LenientVersion::from_str(version).map(LenientVersion::into_version)
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////////////////
+// TESTS GO HERE
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefixes_and_suffixes() {
+        let parsed = LenientVersion::from_str("v1.2").unwrap();
+        assert_eq!(parsed.version(), &Version::new(&[1,2]));
+        assert_eq!(parsed.stripped_prefix(), Some("v"));
+        assert_eq!(parsed.stripped_suffix(), None);
+
+        let parsed = LenientVersion::from_str("release-2.3.4").unwrap();
+        assert_eq!(parsed.version(), &Version::new(&[2,3,4]));
+        assert_eq!(parsed.stripped_prefix(), Some("release-"));
+
+        let parsed = LenientVersion::from_str("1.2.3.Final").unwrap();
+        assert_eq!(parsed.version(), &Version::new(&[1,2,3]));
+        assert_eq!(parsed.stripped_suffix(), Some(".Final"));
+        assert_eq!(parsed.scheme(), DetectedScheme::Dotted);
+
+        let parsed = LenientVersion::from_str("  1.2.3\n").unwrap();
+        assert!(!parsed.was_stripped());
+        assert_eq!(parsed.confidence(), 1.0);
+    }
+
+    #[test]
+    fn schemes() {
+        let parsed = LenientVersion::from_str("2024-05-01").unwrap();
+        assert_eq!(parsed.scheme(), DetectedScheme::Date);
+        assert_eq!(parsed.version(), &Version::new(&[2024,5,1]));
+
+        assert_eq!(LenientVersion::from_str("nightly-20240501").unwrap().scheme(), DetectedScheme::Date);
+        assert_eq!(LenientVersion::from_str("2024.13.01").unwrap().scheme(), DetectedScheme::Dotted);
+
+        let parsed = LenientVersion::from_str("r1234").unwrap();
+        assert_eq!(parsed.scheme(), DetectedScheme::Revision);
+        assert_eq!(parsed.version(), &Version::new(&[1234]));
+
+        let parsed = LenientVersion::from_str("v1_4_2").unwrap();
+        assert_eq!(parsed.scheme(), DetectedScheme::Dotted);
+        assert_eq!(parsed.version(), &Version::new(&[1,4,2]));
+    }
+
+    #[test]
+    fn separators() {
+        // only the first kind of separator is used
+        let parsed = LenientVersion::from_str("1.2.3-4").unwrap();
+        assert_eq!(parsed.version(), &Version::new(&[1,2,3]));
+        assert_eq!(parsed.stripped_suffix(), Some("-4"));
+
+        // a separator without a number isn't part of the version
+        let parsed = LenientVersion::from_str("1.2.").unwrap();
+        assert_eq!(parsed.version(), &Version::new(&[1,2]));
+        assert_eq!(parsed.stripped_suffix(), Some("."));
+    }
+
+    #[test]
+    fn confidence() {
+        let clean = LenientVersion::from_str("1.2.3").unwrap().confidence();
+        let tagged = LenientVersion::from_str("v1.2.3").unwrap().confidence();
+        let known = LenientVersion::from_str("1.2.3.Final").unwrap().confidence();
+        let unknown = LenientVersion::from_str("foo1.2.3-bar").unwrap().confidence();
+        assert!(clean > tagged);
+        assert!(tagged > known);
+        assert!(known > unknown);
+        assert!(unknown >= 0.0);
+    }
+
+    #[test]
+    fn failures() {
+        assert!(LenientVersion::from_str("latest").is_none());
+        assert!(LenientVersion::from_str("").is_none());
+        assert!(LenientVersion::from_str("1.99999999999").is_none());
+        assert!(Version::from_str_lenient("no numbers").is_none());
+
+        assert_eq!("latest".parse::<LenientVersion>().unwrap_err(), LenientError::NoNumbers);
+        assert_eq!("1.99999999999".parse::<LenientVersion>().unwrap_err(), LenientError::TooLarge("99999999999".to_string()));
+        assert_eq!("v1.2".parse::<LenientVersion>().unwrap().version().to_string(), "1.2");
+        assert_eq!(Version::from_str_lenient("V3.0").unwrap(), Version::new(&[3,0]));
+    }
+}
@@ -1,10 +1,70 @@
 //! library for easily working with version numbers in the SEM verison system (a.b.c)
+//!
+//! works without the standard library, turn off the default features and use the `alloc`
+//! feature for everything, or no features for `FixedVersion` only.
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+/// the parts of `alloc` that `std` has in its prelude, so all the modules work the
+/// same with or without `std`
+#[cfg(feature = "alloc")]
+mod prelude {
+    pub(crate) use alloc::string::{String, ToString};
+    pub(crate) use alloc::vec::Vec;
+    pub(crate) use alloc::{vec, format};
+}
+
+mod fixed;
+#[cfg_attr(not(feature = "alloc"), allow(dead_code))] mod identifiers;
 mod versionpart;
-mod version;
+#[cfg(feature = "alloc")] mod parts;
+#[cfg(feature = "alloc")] mod version;
+#[cfg(feature = "alloc")] mod versionref;
+#[cfg(feature = "alloc")] mod nuget;
+#[cfg(feature = "alloc")] mod scheme;
+#[cfg(feature = "alloc")] mod lenient;
+#[cfg(feature = "alloc")] mod parser;
+#[cfg(feature = "alloc")] mod index;
+#[cfg(feature = "alloc")] mod select;
+#[cfg(feature = "alloc")] mod format;
+#[cfg(feature = "alloc")] mod normalize;
+#[cfg(feature = "alloc")] mod builder;
+#[cfg(feature = "alloc")] mod range;
+#[cfg(feature = "alloc")] mod macros;
+#[cfg(feature = "alloc")] mod map;
+#[cfg(feature = "alloc")] mod interval;
+#[cfg(feature = "alloc")] mod sortable;
+#[cfg(feature = "alloc")] mod pack;
+#[cfg(all(feature = "alloc", feature = "serde"))] pub mod serde_format;
 
 // passing through Version, since this will be the main interface in the library
-pub use crate::version::Version;
+#[cfg(feature = "alloc")] pub use crate::version::{Version, PreReleasePolicy, Part};
+#[cfg(feature = "alloc")] pub use crate::versionref::VersionRef;
+#[cfg(feature = "alloc")] pub use crate::nuget::{NuGetVersion, NuGetRange, NuGetError};
+#[cfg(feature = "alloc")] pub use crate::scheme::VersionScheme;
+#[cfg(feature = "alloc")] pub use crate::lenient::{LenientVersion, LenientError, DetectedScheme};
+#[cfg(feature = "alloc")] pub use crate::parser::{VersionParser, ParseError, ParseMode};
+#[cfg(feature = "alloc")] pub use crate::index::VersionIndex;
+#[cfg(feature = "alloc")] pub use crate::select::{VersionSelect, FilterMatching};
+#[cfg(feature = "alloc")] pub use crate::format::VersionFormatter;
+#[cfg(feature = "alloc")] pub use crate::normalize::Normalization;
+#[cfg(feature = "alloc")] pub use crate::builder::{VersionBuilder, VersionError};
+#[cfg(feature = "alloc")] pub use crate::range::{VersionRange, RangeError};
+#[cfg(feature = "alloc")] pub use crate::map::VersionMap;
+#[cfg(feature = "alloc")] pub use crate::interval::IntervalVersionMap;
+#[cfg(feature = "alloc")] pub use crate::pack::{VersionPacking, PackError};
+pub use crate::versionpart::VersionPart;
+pub use crate::fixed::{FixedVersion, CompactVersion, PreRelease};
+
+/// used by the `version!` and `req!` macros, not part of the api
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::macros::{version, range};
+}
 
 #[cfg(test)]
-extern crate serde_test;
\ No newline at end of file
+extern crate serde_test;
@@ -0,0 +1,373 @@
+//! the `version!` and `req!` **macros**, which parse versions while compiling so a mistake
+//! in a version written in the code is a compile error instead of a panic.
+//!
+//! the parsing is done with `const fn`s, everything is checked the same as `Version::from_str`
+//! and `VersionRange::from_str` but a constant version can only have up to 4 components.
+
+use core::cmp::Ordering;
+use core::ops::Bound;
+
+use crate::version::Version;
+use crate::versionpart::VersionPart;
+use crate::parts::{Parts, INLINE_PARTS};
+use crate::range::{VersionRange, Op, split_op, caret_index, tilde_index, is_tighter};
+use crate::identifiers::{find, is_numeric, is_valid_label, parse_number, compare_labels, compare_numbers};
+
+/// a `Version` that is parsed while compiling, so it can be used in a `const` and an
+/// invalid version doesn't compile.
+///
+/// ```
+/// # use version_lp::{Version, version};
+/// const MINIMUM : Version = version!("11.0");
+///
+/// assert!(Version::from_str("11.2.1").unwrap() >= MINIMUM);
+/// assert_eq!(version!("1.2.*-rc.1").to_string(), "1.2.*-rc.1");
+/// ```
+///
+/// ```compile_fail
+/// # use version_lp::{Version, version};
+/// const MINIMUM : Version = version!("11.O");
+/// ```
+#[macro_export]
+macro_rules! version {
+    ($version:expr) => {{
+        const VERSION : $crate::Version = $crate::__private::version($version);
+        VERSION
+    }};
+}
+
+/// a `VersionRange` that is parsed while compiling, so it can be used in a `const` and an
+/// invalid range doesn't compile.
+///
+/// ```
+/// # use version_lp::{Version, VersionRange, req};
+/// const SUPPORTED : VersionRange = req!(">=1.2, <2");
+///
+/// assert!(SUPPORTED.contains(&Version::from_str("1.4.2").unwrap()));
+/// assert_eq!(req!("^0.3").to_string(), ">=0.3, <0.4");
+/// ```
+///
+/// ```compile_fail
+/// # use version_lp::{VersionRange, req};
+/// const SUPPORTED : VersionRange = req!(">=2, <1");
+/// ```
+#[macro_export]
+macro_rules! req {
+    ($range:expr) => {{
+        const RANGE : $crate::VersionRange = $crate::__private::range($range);
+        RANGE
+    }};
+}
+
+/// the version of the crate that is being compiled, `CARGO_PKG_VERSION`, as a constant
+/// `Version` so it can be put in a `static`. `Version::from_cargo_env` reads it in a build script.
+///
+/// ```
+/// # use version_lp::{Version, pkg_version};
+/// static VERSION : Version = pkg_version!();
+///
+/// assert_eq!(VERSION.to_string(), env!("CARGO_PKG_VERSION"));
+/// ```
+#[macro_export]
+macro_rules! pkg_version {
+    () => {
+        $crate::version!(env!("CARGO_PKG_VERSION"))
+    };
+}
+
+/// parses a version while compiling, panics if it isn't valid. used by `version!`
+pub const fn version(version : &'static str) -> Version {
+    match ConstVersion::parse(version) {
+        Ok(version) => version.to_version(),
+        Err(error) => panic!("{}", error),
+    }
+}
+
+/// parses a range while compiling, panics if it isn't valid. used by `req!`
+pub const fn range(range : &'static str) -> VersionRange {
+    match parse_range(range) {
+        Ok((lower, upper)) => VersionRange::from_bounds(to_bound(lower), to_bound(upper)),
+        Err(error) => panic!("{}", error),
+    }
+}
+
+/// a version that can be made in a `const fn`, it is `Copy` so it can be moved around
+/// without dropping anything
+#[derive(Clone, Copy)]
+struct ConstVersion {
+    numbers : [u32; INLINE_PARTS],
+    len : usize,
+    /// if there is a `*` after the numbers
+    wildcard : bool,
+    pre : Option<&'static str>,
+    build : Option<&'static str>,
+}
+
+impl ConstVersion {
+
+    /// parses the version the same way as `Version::from_str`
+    const fn parse(version : &'static str) -> Result<ConstVersion, &'static str> {
+        let bytes = version.as_bytes();
+        if bytes.is_empty() { return Err("the version is empty"); }
+
+        let build_start = find(bytes, b'+', 0, bytes.len());
+        let pre_start = find(bytes, b'-', 0, build_start);
+
+        let pre = match pre_start < build_start {
+            true => Some(slice(version, pre_start + 1, build_start)),
+            false => None,
+        };
+        let build = match build_start < bytes.len() {
+            true => Some(slice(version, build_start + 1, bytes.len())),
+            false => None,
+        };
+        if let Some(pre) = pre {
+            if !is_valid_label(pre) { return Err("the pre-release label isn't made of dot separated alphanumeric identifiers"); }
+        }
+        if let Some(build) = build {
+            if !is_valid_label(build) { return Err("the build metadata isn't made of dot separated alphanumeric identifiers"); }
+        }
+
+        let mut parsed = ConstVersion { numbers : [0; INLINE_PARTS], len : 0, wildcard : false, pre, build };
+
+        let end = if pre_start < build_start { pre_start } else { build_start };
+        let mut start = 0;
+        while start <= end {
+            let section_end = find(bytes, b'.', start, end);
+            let section = slice(version, start, section_end);
+
+            if parsed.len == INLINE_PARTS { return Err("a constant version can only have 4 components"); }
+
+            if let [b'*'] = section.as_bytes() {
+                // we ignore the rest of the string, so we just stop here
+                parsed.wildcard = true;
+                break;
+            }
+            if !is_numeric(section) { return Err("a component isn't a number or a wildcard"); }
+            parsed.numbers[parsed.len] = match parse_number(section) {
+                Some(number) => number,
+                None => return Err("a component is too large for a `u32`"),
+            };
+            parsed.len += 1;
+
+            start = section_end + 1;
+        }
+
+        Ok(parsed)
+    }
+
+    /// the numbers of the version, without the wildcard
+    const fn numbers(&self) -> &[u32] {
+        self.numbers.split_at(self.len).0
+    }
+
+    /// the numbers up to the index with the last one increased, without any labels
+    const fn bump(&self, index : usize) -> Option<ConstVersion> {
+        let mut bumped = ConstVersion { numbers : [0; INLINE_PARTS], len : index + 1, wildcard : false, pre : None, build : None };
+
+        let mut i = 0;
+        while i < index {
+            bumped.numbers[i] = self.numbers[i];
+            i += 1;
+        }
+        bumped.numbers[index] = match self.numbers[index].checked_add(1) {
+            Some(number) => number,
+            None => return None,
+        };
+        Some(bumped)
+    }
+
+    /// the same as `Ord for Version`, for versions without wildcards
+    const fn cmp(&self, other : &ConstVersion) -> Ordering {
+        let depth = if self.len < other.len { self.len } else { other.len };
+
+        let mut i = 0;
+        while i < depth {
+            if self.numbers[i] != other.numbers[i] {
+                return compare_numbers(self.numbers[i] as u64, other.numbers[i] as u64);
+            }
+            i += 1;
+        }
+
+        match (self.pre, other.pre) {
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(_), None) => Ordering::Less,
+            (Some(a), Some(b)) => compare_labels(a, b, false),
+        }
+    }
+
+    const fn to_version(self) -> Version {
+        let mut parts = [VersionPart::Number(0); INLINE_PARTS];
+
+        let mut i = 0;
+        while i < self.len {
+            parts[i] = VersionPart::Number(self.numbers[i]);
+            i += 1;
+        }
+        let mut len = self.len;
+        if self.wildcard {
+            parts[len] = VersionPart::Wildcard('*');
+            len += 1;
+        }
+
+        Version::from_static(Parts::from_inline(parts, len as u8), self.pre, self.build)
+    }
+}
+
+/// parses the comparators the same way as `VersionRange::from_str`
+const fn parse_range(range : &'static str) -> Result<(Bound<ConstVersion>, Bound<ConstVersion>), &'static str> {
+    let bytes = range.as_bytes();
+    let mut lower = Bound::Unbounded;
+    let mut upper = Bound::Unbounded;
+
+    let mut start = 0;
+    while start <= bytes.len() {
+        let end = find(bytes, b',', start, bytes.len());
+        let (op, version) = split_op(slice(range, start, end).trim_ascii());
+
+        let (comparator_lower, comparator_upper) = match comparator_bounds(op, version) {
+            Ok(bounds) => bounds,
+            Err(error) => return Err(error),
+        };
+        lower = tighter(lower, comparator_lower, true);
+        upper = tighter(upper, comparator_upper, false);
+
+        start = end + 1;
+    }
+
+    if let (Bound::Included(lower) | Bound::Excluded(lower), Bound::Included(upper) | Bound::Excluded(upper)) = (lower, upper) {
+        if let Ordering::Greater = lower.cmp(&upper) { return Err("the lower bound of the range is above the upper bound"); }
+    }
+
+    Ok((lower, upper))
+}
+
+/// the bounds of one comparator
+const fn comparator_bounds(op : Op, version : &'static str) -> Result<(Bound<ConstVersion>, Bound<ConstVersion>), &'static str> {
+    let mut version = match ConstVersion::parse(version) {
+        Ok(version) => version,
+        Err(error) => return Err(error),
+    };
+    if version.wildcard {
+        if version.len == 0 {
+            return match op {
+                Op::Exact => Ok((Bound::Unbounded, Bound::Unbounded)),
+                _ => Err("`*` can't be used with a comparison"),
+            };
+        }
+        version.wildcard = false;
+    }
+
+    let index = match op {
+        Op::Caret => caret_index(version.numbers()),
+        _ => tilde_index(version.numbers()),
+    };
+
+    Ok(match op {
+        Op::Exact => (Bound::Included(version), Bound::Included(version)),
+        Op::Greater => (Bound::Excluded(version), Bound::Unbounded),
+        Op::GreaterEq => (Bound::Included(version), Bound::Unbounded),
+        Op::Less => (Bound::Unbounded, Bound::Excluded(version)),
+        Op::LessEq => (Bound::Unbounded, Bound::Included(version)),
+        Op::Caret | Op::Tilde => match version.bump(index) {
+            Some(upper) => (Bound::Included(version), Bound::Excluded(upper)),
+            None => return Err("the upper bound is too large for a `u32`"),
+        },
+    })
+}
+
+/// the tighter of the two lower (or upper) bounds
+const fn tighter(current : Bound<ConstVersion>, new : Bound<ConstVersion>, lower : bool) -> Bound<ConstVersion> {
+    let new_is_tighter = match (current, new) {
+        (_, Bound::Unbounded) => false,
+        (Bound::Unbounded, _) => true,
+        (Bound::Included(c) | Bound::Excluded(c), Bound::Included(n) | Bound::Excluded(n)) => is_tighter(
+            n.cmp(&c), lower,
+            (n.len, matches!(new, Bound::Excluded(_))),
+            (c.len, matches!(current, Bound::Excluded(_))),
+        ),
+    };
+
+    if new_is_tighter { new } else { current }
+}
+
+const fn to_bound(bound : Bound<ConstVersion>) -> Bound<Version> {
+    match bound {
+        Bound::Included(version) => Bound::Included(version.to_version()),
+        Bound::Excluded(version) => Bound::Excluded(version.to_version()),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+/// the part of the text between the indexes, which are always next to an ascii character
+const fn slice(text : &'static str, start : usize, end : usize) -> &'static str {
+    text.split_at(end).0.split_at(start).1
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////////////////
+// TESTS GO HERE
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::{Version, VersionRange, PreReleasePolicy};
+
+    const MINIMUM : Version = version!("11.0");
+    const SUPPORTED : VersionRange = req!(">=1.2, <2");
+
+    #[test]
+    fn versions() {
+        assert_eq!(MINIMUM, Version::new(&[11,0]));
+
+        // the same as parsing at runtime
+        for v in ["1", "0132.1.2", "1.*", "1.2.*.4", "*", "1.2.3-rc.1+abc", "1.2.3+build-5", "4294967295.0"] {
+            let parsed = Version::from_str(v).unwrap();
+            let constant = super::version(v);
+            assert_eq!(constant.to_string(), parsed.to_string());
+            assert_eq!(constant.len(), parsed.len());
+        }
+
+        let pre_release = version!("2.0.0-rc.1");
+        assert_eq!(pre_release.pre(), Some("rc.1"));
+        assert!(pre_release < version!("2.0.0"));
+        assert_eq!(pre_release.clone().with_pre("rc.2").unwrap().to_string(), "2.0.0-rc.2");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn package_version() {
+        assert_eq!(pkg_version!().to_string(), env!("CARGO_PKG_VERSION"));
+        // equality ignores the build metadata, so the strings are compared
+        assert_eq!(Version::from_cargo_env().map(|v| v.to_string()), Some(pkg_version!().to_string()));
+    }
+
+    #[test]
+    fn invalid_versions() {
+        for v in ["", "1..2", "1.a", "1.2.3.4.5", "1.2-", "1.2-rc..1", "1+", "4294967296", "v1.2"] {
+            assert!(super::ConstVersion::parse(v).is_err(), "{}", v);
+        }
+    }
+
+    #[test]
+    fn ranges() {
+        assert!(SUPPORTED.contains(&Version::new(&[1,9,9])));
+        assert!(!SUPPORTED.contains(&Version::new(&[2])));
+
+        // the same as parsing at runtime
+        for r in [">=1.2, <2", "^1.2.3", "^0.0", "~1", "~1.2.3", "1.2.*", "*", "<=3-rc.1", ">=1.2, >=1.2.5, <3, <=2.4", ">1.2, >=1.2.5", ">=1.0.0-beta.2, >=1.0.0-beta.11"] {
+            let parsed = VersionRange::from_str(r).unwrap();
+            let constant = super::range(r);
+            assert_eq!(constant, parsed, "{}", r);
+            assert_eq!(constant.to_string(), parsed.to_string());
+        }
+
+        for r in ["", ">=2, <1", ">*", "^4294967295", "=>1", ">=1.2,"] {
+            assert!(super::parse_range(r).is_err(), "{}", r);
+            assert!(VersionRange::from_str(r).is_none(), "{}", r);
+        }
+
+        assert!(req!(">=2.0.0-rc.1").matches(&version!("2.0.0-rc.2"), PreReleasePolicy::IfRequested));
+        assert_eq!(format!("{:?}", req!("~1.2")), "VersionRange (>=1.2, <1.3)");
+    }
+}
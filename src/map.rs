@@ -0,0 +1,301 @@
+//! a **map** from version patterns to values, that finds the most specific pattern
+//! matching a version.
+
+use core::fmt;
+use core::iter::FromIterator;
+#[cfg(feature = "serde")]
+use core::marker::PhantomData;
+
+use crate::prelude::*;
+use crate::version::Version;
+
+/// values keyed by version patterns (`*`, `11.*`, `11.4`), for settings that change
+/// between versions.
+///
+/// a pattern matches a version if it subsumes it (see `Version::subsumes`), and the most
+/// specific match is the one with the most numbers, with a pattern without a wildcard being
+/// more specific than the same one with a wildcard.
+///
+/// the keys are only wildcard patterns, requirements like `>=1.2, <2` can't be keys because
+/// there isn't a specificity order between a range and a pattern. `IntervalVersionMap` keeps
+/// values for ranges that don't overlap.
+///
+/// ```
+/// # use version_lp::{Version, VersionMap};
+/// let mut map = VersionMap::new();
+/// map.insert(Version::from_str("*").unwrap(), "default");
+/// map.insert(Version::from_str("11.*").unwrap(), "eleven");
+/// map.insert(Version::from_str("11.4").unwrap(), "eleven four");
+///
+/// assert_eq!(map.get(&Version::from_str("11.4.2").unwrap()), Some(&"eleven four"));
+/// assert_eq!(map.get(&Version::from_str("11.2").unwrap()), Some(&"eleven"));
+/// assert_eq!(map.get(&Version::from_str("9.0").unwrap()), Some(&"default"));
+/// ```
+#[derive(Clone)]
+pub struct VersionMap<T> {
+    /// sorted from the most specific pattern, in the order they were added
+    entries : Vec<(Version, T)>,
+}
+
+impl <T> Default for VersionMap<T> {
+    fn default() -> VersionMap<T> {
+        VersionMap { entries : Vec::new() }
+    }
+}
+
+impl <T : PartialEq> PartialEq for VersionMap<T> {
+    fn eq(&self, other : &VersionMap<T>) -> bool {
+        //! maps are equal if they have the same patterns, written the same way, with the same values
+        self.len() == other.len() && self.iter().all(|(pattern, value)| {
+            other.iter().any(|(p, v)| same_key(p, pattern) && v == value)
+        })
+    }
+}
+
+impl <T : fmt::Debug> fmt::Debug for VersionMap<T> {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        //! prints "{x.x: value, ..}", from the most specific pattern
+        f.debug_map().entries(self.iter().map(|(pattern, value)| (pattern.to_string(), value))).finish()
+    }
+}
+
+impl <T> VersionMap<T> {
+
+    /// creates an empty map
+    pub fn new() -> VersionMap<T> {
+        VersionMap::default()
+    }
+
+    /// adds the value for the pattern, returns the value that was replaced if the
+    /// pattern was already in the map (build metadata included)
+    pub fn insert(&mut self, pattern : Version, value : T) -> Option<T> {
+        if let Some(entry) = self.entries.iter_mut().find(|(p, _)| same_key(p, &pattern)) {
+            return Some(core::mem::replace(&mut entry.1, value));
+        }
+
+        let rank = specificity(&pattern);
+        let i = self.entries.partition_point(|(p, _)| specificity(p) >= rank);
+        self.entries.insert(i, (pattern, value));
+        None
+    }
+
+    /// removes the pattern, returns its value if it was in the map
+    pub fn remove(&mut self, pattern : &Version) -> Option<T> {
+        let i = self.entries.iter().position(|(p, _)| same_key(p, pattern))?;
+        Some(self.entries.remove(i).1)
+    }
+
+    /// the value of the most specific pattern that matches the version
+    pub fn get(&self, version : &Version) -> Option<&T> {
+        self.get_key_value(version).map(|(_, value)| value)
+    }
+
+    /// the most specific pattern that matches the version, and its value
+    pub fn get_key_value(&self, version : &Version) -> Option<(&Version, &T)> {
+        self.iter().find(|(pattern, _)| pattern.subsumes(version))
+    }
+
+    /// all the patterns that match the version and their values, from the most specific
+    pub fn get_all<'a>(&'a self, version : &'a Version) -> impl Iterator<Item = (&'a Version, &'a T)> + 'a {
+        self.iter().filter(move |(pattern, _)| pattern.subsumes(version))
+    }
+
+    /// the pairs of patterns that make a lookup ambiguous: patterns that match the same
+    /// versions written differently (`1.2+a` and `1.2+b`), or patterns that overlap but
+    /// neither subsumes the other (`1.2` and `1.2.3.*`, which are both the only match
+    /// for some versions)
+    pub fn conflicts(&self) -> Vec<(&Version, &Version)> {
+        let mut conflicts = Vec::new();
+
+        for (i, (a, _)) in self.entries.iter().enumerate() {
+            for (b, _) in self.entries[i + 1 ..].iter() {
+                let (a_subsumes, b_subsumes) = (a.subsumes(b), b.subsumes(a));
+                if (a_subsumes && b_subsumes) || (!a_subsumes && !b_subsumes && a.overlaps(b)) {
+                    conflicts.push((a, b));
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// the number of patterns in the map
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// checks if there are no patterns in the map
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// all the patterns and their values, from the most specific
+    pub fn iter(&self) -> impl Iterator<Item = (&Version, &T)> {
+        self.entries.iter().map(|(pattern, value)| (pattern, value))
+    }
+}
+
+impl <T> FromIterator<(Version, T)> for VersionMap<T> {
+    fn from_iter<I : IntoIterator<Item = (Version, T)>>(iter : I) -> VersionMap<T> {
+        let mut map = VersionMap::new();
+        for (pattern, value) in iter {
+            map.insert(pattern, value);
+        }
+        map
+    }
+}
+
+/// checks if the patterns are written the same way, the same wildcard and build metadata
+/// included, without rendering them
+fn same_key(a : &Version, b : &Version) -> bool {
+    a.len() == b.len()
+        && a.parts().zip(b.parts()).all(|(a, b)| a.number() == b.number() && a.wildcard() == b.wildcard())
+        && a.pre() == b.pre() && a.build() == b.build()
+}
+
+/// how specific the pattern is, the number of numbers and then if it doesn't end in a wildcard
+fn specificity(pattern : &Version) -> (usize, bool) {
+    let wild = pattern.has_wildcards();
+    (pattern.len() - wild as usize, !wild)
+}
+
+#[cfg(feature = "serde")]
+impl <T : serde::Serialize> serde::Serialize for VersionMap<T> {
+    fn serialize<S>(&self, serializer : S) -> Result<S::Ok, S::Error> where S : serde::Serializer {
+        //! serializes as a map of the patterns to the values, from the most specific
+        serializer.collect_map(self.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl <'de, T : serde::Deserialize<'de>> serde::Deserialize<'de> for VersionMap<T> {
+    fn deserialize<D>(deserializer : D) -> Result<VersionMap<T>, D::Error> where D : serde::Deserializer<'de> {
+        //! fails if a pattern is there twice or if there are conflicts, see `VersionMap::conflicts`
+        deserializer.deserialize_map(VersionMapVisitor(PhantomData))
+    }
+}
+
+#[cfg(feature = "serde")]
+struct VersionMapVisitor<T>(PhantomData<T>);
+
+#[cfg(feature = "serde")]
+impl <'de, T : serde::Deserialize<'de>> serde::de::Visitor<'de> for VersionMapVisitor<T> {
+    type Value = VersionMap<T>;
+
+    fn expecting(&self, formatter : &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map of version patterns")
+    }
+
+    fn visit_map<A>(self, mut access : A) -> Result<VersionMap<T>, A::Error> where A : serde::de::MapAccess<'de> {
+        use serde::de::Error;
+
+        let mut map = VersionMap::new();
+        while let Some((pattern, value)) = access.next_entry::<Version, T>()? {
+            let key = pattern.to_string();
+            if map.insert(pattern, value).is_some() {
+                return Err(Error::custom(format!("the pattern `{}` is in the map twice", key)));
+            }
+        }
+
+        if let Some((a, b)) = map.conflicts().first() {
+            return Err(Error::custom(format!("the patterns `{}` and `{}` are ambiguous", a, b)));
+        }
+        Ok(map)
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////////////////
+// TESTS GO HERE
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map() -> VersionMap<u32> {
+        ["11.4", "*", "11.*", "11.4.*", "12", "11.4.2-rc.1"].iter()
+            .enumerate()
+            .map(|(i, pattern)| (Version::from_str(pattern).unwrap(), i as u32))
+            .collect()
+    }
+
+    #[test]
+    fn most_specific() {
+        let map = map();
+        let get = |v : &str| map.get(&Version::from_str(v).unwrap()).copied();
+
+        assert_eq!(get("11.4.2"), Some(0));
+        assert_eq!(get("11.4.2-rc.1"), Some(5));
+        assert_eq!(get("11.4.0-beta"), Some(3));
+        assert_eq!(get("11.2"), Some(2));
+        assert_eq!(get("12.0.1"), Some(4));
+        assert_eq!(get("3"), Some(1));
+        assert_eq!(get("11.*"), Some(2));
+
+        let all : Vec<u32> = map.get_all(&Version::from_str("11.4.2").unwrap()).map(|(_, v)| *v).collect();
+        assert_eq!(all, vec![0, 3, 2, 1]);
+
+        let patterns : Vec<String> = map.iter().map(|(p, _)| p.to_string()).collect();
+        assert_eq!(patterns, vec!["11.4.2-rc.1", "11.4", "11.4.*", "12", "11.*", "*"]);
+    }
+
+    #[test]
+    fn insert_and_remove() {
+        let mut map = map();
+
+        assert_eq!(map.insert(Version::from_str("11.*").unwrap(), 20), Some(2));
+        assert_eq!(map.get(&Version::from_str("11.1").unwrap()), Some(&20));
+        assert_eq!(map.len(), 6);
+
+        assert_eq!(map.remove(&Version::from_str("11.4").unwrap()), Some(0));
+        assert_eq!(map.remove(&Version::from_str("11.4").unwrap()), None);
+        assert_eq!(map.get(&Version::from_str("11.4.2").unwrap()), Some(&3));
+
+        // equal versions written differently are different patterns
+        assert_eq!(map.insert(Version::from_str("11.4.0").unwrap(), 30), None);
+        assert_eq!(map.insert(Version::from_str("11.4.0+abc").unwrap(), 31), None);
+        assert_eq!(map.remove(&Version::from_str("11.4.0").unwrap()), Some(30));
+        assert_eq!(map.len(), 6);
+    }
+
+    #[test]
+    fn conflicts() {
+        assert!(map().conflicts().is_empty());
+
+        let mut map = map();
+        map.insert(Version::from_str("11.4+abc").unwrap(), 6);
+        map.insert(Version::from_str("11.4.2.*").unwrap(), 7);
+
+        let conflicts : Vec<(String, String)> = map.conflicts().iter().map(|(a, b)| (a.to_string(), b.to_string())).collect();
+        assert_eq!(conflicts, vec![
+            ("11.4.2.*".to_string(), "11.4".to_string()),
+            ("11.4.2.*".to_string(), "11.4+abc".to_string()),
+            ("11.4".to_string(), "11.4+abc".to_string()),
+        ]);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde() {
+        use serde_test::{Token, Configure, assert_tokens, assert_de_tokens_error};
+
+        let map : VersionMap<u32> = [("11.4", 0), ("*", 1)].iter().map(|(p, v)| (Version::from_str(p).unwrap(), *v)).collect();
+        assert_tokens(&map.readable(), &[
+            Token::Map { len : Some(2) },
+            Token::Str("11.4"), Token::U32(0),
+            Token::Str("*"), Token::U32(1),
+            Token::MapEnd,
+        ]);
+
+        assert_de_tokens_error::<serde_test::Readable<VersionMap<u32>>>(&[
+            Token::Map { len : Some(2) },
+            Token::Str("11.4"), Token::U32(0),
+            Token::Str("11.04"), Token::U32(1),
+        ], "the pattern `11.4` is in the map twice");
+
+        assert_de_tokens_error::<serde_test::Readable<VersionMap<u32>>>(&[
+            Token::Map { len : Some(2) },
+            Token::Str("11.4"), Token::U32(0),
+            Token::Str("11.4+abc"), Token::U32(1),
+            Token::MapEnd,
+        ], "the patterns `11.4` and `11.4+abc` are ambiguous");
+    }
+}
@@ -0,0 +1,121 @@
+//! **normalizing** versions, so versions that are equal also render the same.
+
+use crate::version::Version;
+use crate::versionpart::VersionPart;
+
+/// the rules for `Version::normalize`. the default rules are the ones `Version::canonical` uses,
+/// trailing zeros are trimmed and then it is padded to 3 components, so `1.2`, `1.2.0` and
+/// `1.2.0.0` are all `1.2.0`.
+///
+/// the normalized version never has a prefix and doesn't have build metadata unless
+/// `keep_build` is set, numbers are always written without leading zeros.
+///
+/// ```
+/// # use version_lp::{Version, Normalization};
+/// let rules = Normalization::new().pad_to(4);
+/// assert_eq!(Version::from_str("1.2").unwrap().normalize(&rules).to_string(), "1.2.0.0");
+///
+/// let rules = Normalization::new().pad_to(1);
+/// assert_eq!(Version::from_str("1.2.0.0").unwrap().normalize(&rules).to_string(), "1.2");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Normalization {
+    trim_trailing_zeros : bool,
+    pad_to : usize,
+    keep_build : bool,
+}
+
+impl Default for Normalization {
+    fn default() -> Normalization {
+        //! the rules of `Version::canonical`
+        Normalization { trim_trailing_zeros : true, pad_to : 3, keep_build : false }
+    }
+}
+
+impl Normalization {
+
+    /// the rules of `Version::canonical`
+    pub fn new() -> Normalization {
+        Normalization::default()
+    }
+
+    /// removes the `0` components at the end, `1.2.0.0` is `1.2`. on by default
+    pub fn trim_trailing_zeros(mut self, trim : bool) -> Normalization {
+        self.trim_trailing_zeros = trim;
+        self
+    }
+
+    /// adds `0` components until there are at least this many, after trimming. `3` by default
+    pub fn pad_to(mut self, components : usize) -> Normalization {
+        self.pad_to = components;
+        self
+    }
+
+    /// keeps the build metadata, it isn't used when comparing so it is dropped by default
+    pub fn keep_build(mut self, keep : bool) -> Normalization {
+        self.keep_build = keep;
+        self
+    }
+
+    /// applies the rules to a copy of the version
+    pub fn normalize(&self, version : &Version) -> Version {
+        let mut normalized = version.clone();
+        normalized.set_prefix(None);
+        if !self.keep_build { normalized.set_build(None); }
+
+        if self.trim_trailing_zeros {
+            let parts = normalized.components();
+            let zeros = parts.iter().rev().take_while(|part| **part == VersionPart::Number(0)).count();
+            let len = parts.len() - zeros;
+            normalized.truncate(len);
+        }
+        normalized.pad_to(self.pad_to);
+        normalized
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////////////////
+// TESTS GO HERE
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonical() {
+        let canonical = |v : &str| Version::from_str(v).unwrap().canonical().to_string();
+
+        assert_eq!(canonical("1.2"), "1.2.0");
+        assert_eq!(canonical("1.2.0"), "1.2.0");
+        assert_eq!(canonical("1.2.0.0"), "1.2.0");
+        assert_eq!(canonical("1.2.0.1"), "1.2.0.1");
+        assert_eq!(canonical("0132.1.2"), "132.1.2");
+        assert_eq!(canonical("0.0"), "0.0.0");
+        assert_eq!(canonical("1.0.*"), "1.0.*");
+        assert_eq!(canonical("2.0.0-rc.1+abc"), "2.0.0-rc.1");
+    }
+
+    #[test]
+    fn rules() {
+        let version = Version::from_str("3.0.0+abc").unwrap();
+
+        assert_eq!(version.normalize(&Normalization::new().trim_trailing_zeros(false).pad_to(4)).to_string(), "3.0.0.0");
+        assert_eq!(version.normalize(&Normalization::new().pad_to(0)).to_string(), "3");
+        assert_eq!(version.normalize(&Normalization::new().keep_build(true)).to_string(), "3.0.0+abc");
+    }
+
+    #[test]
+    fn truncate_and_pad() {
+        let mut version = Version::from_str("1.2.3.4-rc").unwrap();
+        version.truncate(2);
+        assert_eq!(version.to_string(), "1.2-rc");
+        version.truncate(0);
+        assert_eq!(version.to_string(), "1-rc");
+        version.pad_to(3);
+        assert_eq!(version.to_string(), "1.0.0-rc");
+
+        let mut wild = Version::from_str("1.*").unwrap();
+        wild.pad_to(3);
+        assert_eq!(wild.to_string(), "1.*");
+    }
+}
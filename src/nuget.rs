@@ -0,0 +1,881 @@
+//! versions and version ranges following the **NuGet** rules.
+//!
+//! nuget versions are `major.minor.patch[.revision][-release][+metadata]`,
+//! missing parts are treated as `0` so `1.0` and `1.0.0.0` are the same version.
+
+use core::fmt;
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+use core::str::FromStr;
+
+use crate::prelude::*;
+use crate::identifiers::{is_numeric, is_valid_label, compare_labels};
+
+/// the string that couldn't be parsed as a nuget version or range
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum NuGetError {
+    InvalidVersion(String),
+    InvalidRange(String),
+}
+
+impl fmt::Display for NuGetError {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NuGetError::InvalidVersion(version) => write!(f, "`{}` isn't a valid nuget version", version),
+            NuGetError::InvalidRange(range) => write!(f, "`{}` isn't a valid nuget range", range),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NuGetError { }
+
+#[derive(Clone)]
+pub struct NuGetVersion {
+    major : u32,
+    minor : u32,
+    patch : u32,
+    revision : u32,
+    release : Option<String>,
+    metadata : Option<String>,
+}
+
+impl NuGetVersion {
+
+    /// creates a new release version from the numeric parts
+    pub fn new(major : u32, minor : u32, patch : u32, revision : u32) -> NuGetVersion {
+        NuGetVersion { major, minor, patch, revision, release : None, metadata : None }
+    }
+
+    /// parses a nuget version string, `1`, `1.2`, `1.2.3`, `1.2.3.4` with an optional
+    /// `-release` label and `+metadata`. `str::parse` gives a `NuGetError` instead of `None`
+    // kept next to `FromStr` for the `Option` api, like `Version::from_str`
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(version : &str) -> Option<NuGetVersion> {
+        NuGetVersion::parse(version)
+    }
+
+    fn parse(version : &str) -> Option<NuGetVersion> {
+        let (rest, metadata) = match version.find('+') {
+            Some(i) => (&version[..i], Some(&version[i+1..])),
+            None => (version, None),
+        };
+        let (numbers, release) = match rest.find('-') {
+            Some(i) => (&rest[..i], Some(&rest[i+1..])),
+            None => (rest, None),
+        };
+
+        let mut parts = [0u32; 4];
+        for (i, section) in numbers.split('.').enumerate() {
+            if i >= parts.len() || !is_numeric(section) { return None; }
+            parts[i] = section.parse::<u32>().ok()?;
+        }
+
+        if let Some(release) = release {
+            if !is_valid_label(release) { return None; }
+        }
+        if let Some(metadata) = metadata {
+            if !is_valid_label(metadata) { return None; }
+        }
+
+        Some(NuGetVersion {
+            major : parts[0],
+            minor : parts[1],
+            patch : parts[2],
+            revision : parts[3],
+            release : release.map(String::from),
+            metadata : metadata.map(String::from),
+        })
+    }
+
+    pub fn major(&self) -> u32 { self.major }
+    pub fn minor(&self) -> u32 { self.minor }
+    pub fn patch(&self) -> u32 { self.patch }
+    pub fn revision(&self) -> u32 { self.revision }
+
+    /// the pre-release label, without the leading `-`
+    pub fn release(&self) -> Option<&str> { self.release.as_deref() }
+
+    /// the build metadata, without the leading `+`
+    pub fn metadata(&self) -> Option<&str> { self.metadata.as_deref() }
+
+    /// checks if this is a pre-release version
+    pub fn is_prerelease(&self) -> bool { self.release.is_some() }
+
+    /// returns the normalized nuget string, `x.x.x[.x][-release]`
+    ///
+    /// the revision is only rendered when it isn't zero and the metadata is
+    /// dropped, same as nuget does.
+    pub fn to_normalized_string(&self) -> String {
+        let mut rendered_string = format!("{}.{}.{}", self.major, self.minor, self.patch);
+        if self.revision != 0 {
+            rendered_string += &format!(".{}", self.revision);
+        }
+        if let Some(ref release) = self.release {
+            rendered_string += &format!("-{}", release);
+        }
+        rendered_string
+    }
+
+    /// returns the normalized string including the `+metadata`
+    pub fn to_full_string(&self) -> String {
+        match self.metadata {
+            Some(ref metadata) => format!("{}+{}", self.to_normalized_string(), metadata),
+            None => self.to_normalized_string(),
+        }
+    }
+
+    fn numbers(&self) -> [u32; 4] {
+        [self.major, self.minor, self.patch, self.revision]
+    }
+
+    /// the normalized string without the release label
+    fn numbers_string(&self) -> String {
+        NuGetVersion::new(self.major, self.minor, self.patch, self.revision).to_normalized_string()
+    }
+}
+
+impl PartialEq for NuGetVersion {
+    fn eq(&self, other : &NuGetVersion) -> bool {
+        //! metadata is ignored, same as nuget
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for NuGetVersion { }
+
+impl Hash for NuGetVersion {
+    fn hash<H : Hasher>(&self, state : &mut H) {
+        //! hashes the label the way it is compared, numbers without leading zeros
+        //! and text in lowercase, so `rc.01` and `RC.1` hash the same
+        self.numbers().hash(state);
+        if let Some(ref release) = self.release {
+            for identifier in release.split('.') {
+                match is_numeric(identifier) {
+                    true => identifier.trim_start_matches('0').hash(state),
+                    false => identifier.to_ascii_lowercase().hash(state),
+                }
+            }
+        }
+    }
+}
+
+impl Ord for NuGetVersion {
+    fn cmp(&self, other : &NuGetVersion) -> Ordering {
+        //! a release is always greater than any of its pre-releases
+        self.numbers().cmp(&other.numbers()).then_with(|| {
+            match (&self.release, &other.release) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => compare_labels(a, b, true),
+            }
+        })
+    }
+}
+
+impl PartialOrd for NuGetVersion {
+    fn partial_cmp(&self, other : &NuGetVersion) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Debug for NuGetVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        //! prints "NuGetVersion (x.x.x)"
+        write!(f, "NuGetVersion ({})", self.to_full_string())
+    }
+}
+
+impl fmt::Display for NuGetVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        //! prints the normalized "x.x.x"
+        write!(f, "{}", self.to_normalized_string())
+    }
+}
+
+impl FromStr for NuGetVersion {
+    type Err = NuGetError;
+
+    fn from_str(version : &str) -> Result<NuGetVersion, NuGetError> {
+        NuGetVersion::parse(version).ok_or_else(|| NuGetError::InvalidVersion(version.to_string()))
+    }
+}
+
+/// how a floating range picks its versions
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum Float {
+    /// `*`
+    Major,
+    /// `1.*`
+    Minor,
+    /// `1.2.*`
+    Patch,
+    /// `1.2.3.*`
+    Revision,
+    /// `1.2.3-*` or `1.2.3-beta*`
+    Release,
+}
+
+/// a nuget version range, either interval notation (`[1.0,2.0)`), a bare
+/// minimum version (`1.0`) or a floating version (`1.*`, `1.0.0-*`, `1.*-*`).
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct NuGetRange {
+    min : Option<NuGetVersion>,
+    min_inclusive : bool,
+    max : Option<NuGetVersion>,
+    max_inclusive : bool,
+    float : Option<(Float, String)>,
+    /// the start of the pre-release labels a numeric float takes, `rc` in `1.*-rc*`.
+    /// without it the float only takes releases
+    float_release : Option<String>,
+}
+
+impl NuGetRange {
+
+    /// parses a nuget range string.
+    ///
+    /// - `1.0` is `1.0 <= x`
+    /// - `[1.0]` is `x == 1.0`
+    /// - `(1.0,)` is `1.0 < x`, `[1.0,2.0)` is `1.0 <= x < 2.0` etc..
+    /// - `1.*`, `1.2.*`, `1.0.0-*` are floating and match anything with the given prefix,
+    ///   `1.*` only matches releases and `1.*-*` matches pre-releases too
+    ///
+    /// `str::parse` gives a `NuGetError` instead of `None`
+    // kept next to `FromStr`, the same as `NuGetVersion::from_str`
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(range : &str) -> Option<NuGetRange> {
+        NuGetRange::parse(range)
+    }
+
+    fn parse(range : &str) -> Option<NuGetRange> {
+        let range = range.trim();
+
+        let first = range.chars().next()?;
+        if first != '[' && first != '(' {
+            if range.ends_with('*') {
+                return NuGetRange::parse_float(range);
+            }
+            return Some(NuGetRange {
+                min : Some(NuGetVersion::parse(range)?),
+                min_inclusive : true,
+                max : None,
+                max_inclusive : false,
+                float : None,
+                float_release : None,
+            });
+        }
+
+        let min_inclusive = first == '[';
+        let max_inclusive = match range.chars().last()? {
+            ']' => true,
+            ')' => false,
+            _ => return None,
+        };
+        if range.len() < 2 { return None; }
+        let inner = &range[1..range.len()-1];
+
+        let parse_bound = |bound : &str| -> Option<Option<NuGetVersion>> {
+            let bound = bound.trim();
+            if bound.is_empty() { Some(None) } else { NuGetVersion::parse(bound).map(Some) }
+        };
+
+        match inner.find(',') {
+            // `[1.0]` is the only valid form without a comma
+            None => {
+                if !min_inclusive || !max_inclusive { return None; }
+                let exact = parse_bound(inner)??;
+                Some(NuGetRange {
+                    min : Some(exact.clone()),
+                    min_inclusive : true,
+                    max : Some(exact),
+                    max_inclusive : true,
+                    float : None,
+                    float_release : None,
+                })
+            },
+            Some(i) => {
+                let min = parse_bound(&inner[..i])?;
+                let max = parse_bound(&inner[i+1..])?;
+                if min.is_none() && max.is_none() { return None; }
+                if let (Some(ref min), Some(ref max)) = (&min, &max) {
+                    if min > max { return None; }
+                    if min == max && !(min_inclusive && max_inclusive) { return None; }
+                }
+                Some(NuGetRange { min, min_inclusive, max, max_inclusive, float : None, float_release : None })
+            }
+        }
+    }
+
+    fn parse_float(range : &str) -> Option<NuGetRange> {
+        let prefix = &range[..range.len()-1];
+
+        // `1.*-*` and `*-rc*`, a numeric float that takes pre-releases too
+        if let Some(i) = prefix.find("*-") {
+            let label = &prefix[i+2..];
+            if !label.is_empty() && !is_valid_label(label.strip_suffix('.').unwrap_or(label)) { return None; }
+            let mut float = NuGetRange::parse_float(&prefix[..=i])?;
+            if let Some((Float::Release, _)) = float.float { return None; }
+            float.float_release = Some(label.to_string());
+            return Some(float);
+        }
+
+        if prefix.is_empty() {
+            return Some(NuGetRange {
+                min : Some(NuGetVersion::new(0, 0, 0, 0)),
+                min_inclusive : true,
+                max : None,
+                max_inclusive : false,
+                float : Some((Float::Major, String::new())),
+                float_release : None,
+            });
+        }
+
+        // `1.0.0-*` and `1.0.0-beta*`, floating on the release label
+        if let Some(i) = prefix.find('-') {
+            let label = &prefix[i+1..];
+            if !label.is_empty() && !is_valid_label(label.strip_suffix('.').unwrap_or(label)) { return None; }
+            let mut min = NuGetVersion::parse(&prefix[..i])?;
+            min.release = Some(if label.is_empty() { String::from("0") } else { label.to_string() });
+            return Some(NuGetRange {
+                min : Some(min),
+                min_inclusive : true,
+                max : None,
+                max_inclusive : false,
+                float : Some((Float::Release, label.to_string())),
+                float_release : None,
+            });
+        }
+
+        // `1.*`, `1.2.*`, `1.2.3.*`, floating on a numeric part
+        if !prefix.ends_with('.') { return None; }
+        let numbers = &prefix[..prefix.len()-1];
+        let min = NuGetVersion::parse(numbers)?;
+        let float = match numbers.split('.').count() {
+            1 => Float::Minor,
+            2 => Float::Patch,
+            3 => Float::Revision,
+            _ => return None,
+        };
+
+        Some(NuGetRange {
+            min : Some(min),
+            min_inclusive : true,
+            max : None,
+            max_inclusive : false,
+            float : Some((float, numbers.to_string())),
+            float_release : None,
+        })
+    }
+
+    /// the lower bound of the range and if it is inclusive
+    pub fn min(&self) -> Option<(&NuGetVersion, bool)> {
+        self.min.as_ref().map(|min| (min, self.min_inclusive))
+    }
+
+    /// the upper bound of the range and if it is inclusive
+    pub fn max(&self) -> Option<(&NuGetVersion, bool)> {
+        self.max.as_ref().map(|max| (max, self.max_inclusive))
+    }
+
+    /// checks if the range is a floating range (`1.*`, `1.0.0-*`)
+    pub fn is_floating(&self) -> bool {
+        self.float.is_some()
+    }
+
+    /// checks if the version is inside of the range
+    pub fn satisfies(&self, version : &NuGetVersion) -> bool {
+        if let Some((float, ref prefix)) = self.float {
+            let min = self.min.as_ref().unwrap();
+            let numbers = version.numbers();
+            let label_starts_with = |prefix : &str| match version.release {
+                Some(ref release) => release.to_ascii_lowercase().starts_with(&prefix.to_ascii_lowercase()),
+                None => true,
+            };
+            // a numeric float only takes pre-releases if it floats the label too, `1.*-*`
+            let release = match self.float_release {
+                Some(ref prefix) => label_starts_with(prefix),
+                None => !version.is_prerelease(),
+            };
+            return match float {
+                Float::Major => release,
+                Float::Minor => release && numbers[..1] == min.numbers()[..1],
+                Float::Patch => release && numbers[..2] == min.numbers()[..2],
+                Float::Revision => release && numbers[..3] == min.numbers()[..3],
+                Float::Release => numbers == min.numbers() && label_starts_with(prefix),
+            };
+        }
+
+        if let Some(ref min) = self.min {
+            match version.cmp(min) {
+                Ordering::Less => return false,
+                Ordering::Equal if !self.min_inclusive => return false,
+                _ => { },
+            }
+        }
+        if let Some(ref max) = self.max {
+            match version.cmp(max) {
+                Ordering::Greater => return false,
+                Ordering::Equal if !self.max_inclusive => return false,
+                _ => { },
+            }
+        }
+
+        true
+    }
+
+    /// picks the version nuget would resolve to from a list.
+    ///
+    /// floating ranges pick the highest matching version, everything else picks
+    /// the lowest matching version.
+    pub fn best_match<'a>(&self, list : &'a [NuGetVersion]) -> Option<&'a NuGetVersion> {
+        let matching = list.iter().filter(|version| self.satisfies(version));
+        if self.is_floating() { matching.max() } else { matching.min() }
+    }
+
+    /// returns the normalized range string
+    pub fn to_normalized_string(&self) -> String {
+        if let Some((float, ref prefix)) = self.float {
+            let rendered = match float {
+                Float::Major => String::from("*"),
+                Float::Release => format!("{}-{}*", self.min.as_ref().unwrap().numbers_string(), prefix),
+                _ => format!("{}.*", prefix),
+            };
+            return match self.float_release {
+                Some(ref label) => format!("{}-{}*", rendered, label),
+                None => rendered,
+            };
+        }
+
+        match (&self.min, &self.max) {
+            (Some(min), None) if self.min_inclusive => min.to_normalized_string(),
+            (Some(min), Some(max)) if min == max => format!("[{}]", min),
+            (min, max) => format!("{}{},{}{}",
+                if self.min_inclusive { '[' } else { '(' },
+                min.as_ref().map(NuGetVersion::to_normalized_string).unwrap_or_default(),
+                max.as_ref().map(NuGetVersion::to_normalized_string).unwrap_or_default(),
+                if self.max_inclusive { ']' } else { ')' }),
+        }
+    }
+}
+
+impl fmt::Debug for NuGetRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        //! prints "NuGetRange ([x.x.x,x.x.x))"
+        write!(f, "NuGetRange ({})", self.to_normalized_string())
+    }
+}
+
+impl fmt::Display for NuGetRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_normalized_string())
+    }
+}
+
+impl FromStr for NuGetRange {
+    type Err = NuGetError;
+
+    fn from_str(range : &str) -> Result<NuGetRange, NuGetError> {
+        NuGetRange::parse(range).ok_or_else(|| NuGetError::InvalidRange(range.to_string()))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for NuGetVersion {
+    fn serialize<S>(&self, serializer : S) -> Result<S::Ok, S::Error> where S : serde::Serializer {
+        //! serializes as "x.x.x[.x][-release][+metadata]", or a tuple of the parts if
+        //! the format isn't human readable
+        use serde::ser::SerializeTuple;
+
+        if serializer.is_human_readable() {
+            return serializer.serialize_str(&self.to_full_string());
+        }
+
+        let mut state = serializer.serialize_tuple(6)?;
+        for number in self.numbers().iter() {
+            state.serialize_element(number)?;
+        }
+        state.serialize_element(&self.release)?;
+        state.serialize_element(&self.metadata)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl <'de> serde::Deserialize<'de> for NuGetVersion {
+    fn deserialize<D>(deserializer : D) -> Result<NuGetVersion, D::Error> where D : serde::Deserializer<'de> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(NuGetVisitor)
+        } else {
+            deserializer.deserialize_tuple(6, NuGetVisitor)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+struct NuGetVisitor;
+
+#[cfg(feature = "serde")]
+impl <'de> serde::de::Visitor<'de> for NuGetVisitor {
+    type Value = NuGetVersion;
+
+    fn expecting(&self, formatter : &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("nuget version")
+    }
+
+    fn visit_str<E>(self, string : &str) -> Result<NuGetVersion, E> where E : serde::de::Error {
+        NuGetVersion::from_str(string).ok_or_else(|| E::invalid_value(serde::de::Unexpected::Str(string), &self))
+    }
+
+    fn visit_seq<A>(self, mut seq : A) -> Result<NuGetVersion, A::Error> where A : serde::de::SeqAccess<'de> {
+        use serde::de::{Error, Unexpected};
+
+        let mut numbers = [0u32; 4];
+        for (i, number) in numbers.iter_mut().enumerate() {
+            *number = seq.next_element()?.ok_or_else(|| Error::invalid_length(i, &self))?;
+        }
+        let release : Option<String> = seq.next_element()?.ok_or_else(|| Error::invalid_length(4, &self))?;
+        let metadata : Option<String> = seq.next_element()?.ok_or_else(|| Error::invalid_length(5, &self))?;
+
+        for label in release.iter().chain(metadata.iter()) {
+            if !is_valid_label(label) { return Err(Error::invalid_value(Unexpected::Str(label), &self)); }
+        }
+
+        let mut version = NuGetVersion::new(numbers[0], numbers[1], numbers[2], numbers[3]);
+        version.release = release;
+        version.metadata = metadata;
+        Ok(version)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Float {
+    fn to_u8(self) -> u8 {
+        match self {
+            Float::Major => 0,
+            Float::Minor => 1,
+            Float::Patch => 2,
+            Float::Revision => 3,
+            Float::Release => 4,
+        }
+    }
+
+    fn from_u8(float : u8) -> Option<Float> {
+        match float {
+            0 => Some(Float::Major),
+            1 => Some(Float::Minor),
+            2 => Some(Float::Patch),
+            3 => Some(Float::Revision),
+            4 => Some(Float::Release),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for NuGetRange {
+    fn serialize<S>(&self, serializer : S) -> Result<S::Ok, S::Error> where S : serde::Serializer {
+        //! serializes as the normalized range string, or a tuple of the bounds if the
+        //! format isn't human readable
+        use serde::ser::SerializeTuple;
+
+        if serializer.is_human_readable() {
+            return serializer.serialize_str(&self.to_normalized_string());
+        }
+
+        // the label the float takes, for `1.0.0-beta*` and `1.*-beta*`
+        let float = self.float.as_ref().map(|(float, _)| float.to_u8());
+        let label = match self.float {
+            Some((Float::Release, ref label)) => Some(label.as_str()),
+            _ => self.float_release.as_deref(),
+        };
+
+        let mut state = serializer.serialize_tuple(6)?;
+        state.serialize_element(&self.min)?;
+        state.serialize_element(&self.min_inclusive)?;
+        state.serialize_element(&self.max)?;
+        state.serialize_element(&self.max_inclusive)?;
+        state.serialize_element(&float)?;
+        state.serialize_element(&label)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl <'de> serde::Deserialize<'de> for NuGetRange {
+    fn deserialize<D>(deserializer : D) -> Result<NuGetRange, D::Error> where D : serde::Deserializer<'de> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(NuGetRangeVisitor)
+        } else {
+            deserializer.deserialize_tuple(6, NuGetRangeVisitor)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+struct NuGetRangeVisitor;
+
+#[cfg(feature = "serde")]
+impl <'de> serde::de::Visitor<'de> for NuGetRangeVisitor {
+    type Value = NuGetRange;
+
+    fn expecting(&self, formatter : &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("nuget version range")
+    }
+
+    fn visit_str<E>(self, string : &str) -> Result<NuGetRange, E> where E : serde::de::Error {
+        NuGetRange::from_str(string).ok_or_else(|| E::invalid_value(serde::de::Unexpected::Str(string), &self))
+    }
+
+    fn visit_seq<A>(self, mut seq : A) -> Result<NuGetRange, A::Error> where A : serde::de::SeqAccess<'de> {
+        use serde::de::{Error, Unexpected};
+
+        let min : Option<NuGetVersion> = seq.next_element()?.ok_or_else(|| Error::invalid_length(0, &self))?;
+        let min_inclusive : bool = seq.next_element()?.ok_or_else(|| Error::invalid_length(1, &self))?;
+        let max : Option<NuGetVersion> = seq.next_element()?.ok_or_else(|| Error::invalid_length(2, &self))?;
+        let max_inclusive : bool = seq.next_element()?.ok_or_else(|| Error::invalid_length(3, &self))?;
+        let float : Option<u8> = seq.next_element()?.ok_or_else(|| Error::invalid_length(4, &self))?;
+        let label : Option<String> = seq.next_element()?.ok_or_else(|| Error::invalid_length(5, &self))?;
+
+        let (float, float_release) = match (float, &min) {
+            (None, _) => (None, None),
+            (Some(float), Some(min)) => {
+                let float = Float::from_u8(float).ok_or_else(|| Error::invalid_value(Unexpected::Unsigned(float as u64), &self))?;
+                let numbers = min.numbers();
+                let prefix = match float {
+                    Float::Major => String::new(),
+                    Float::Minor => format!("{}", numbers[0]),
+                    Float::Patch => format!("{}.{}", numbers[0], numbers[1]),
+                    Float::Revision => format!("{}.{}.{}", numbers[0], numbers[1], numbers[2]),
+                    Float::Release => label.clone().unwrap_or_default(),
+                };
+                let float_release = if float == Float::Release { None } else { label };
+                (Some((float, prefix)), float_release)
+            },
+            (Some(_), None) => return Err(Error::custom("a floating range needs a minimum version")),
+        };
+
+        // the parts have to make a range that `from_str` would make, so it is checked the same way
+        let range = NuGetRange { min, min_inclusive, max, max_inclusive, float, float_release };
+        let rendered = range.to_normalized_string();
+        match NuGetRange::parse(&rendered) {
+            Some(ref parsed) if *parsed == range => Ok(range),
+            _ => Err(Error::custom(NuGetError::InvalidRange(rendered))),
+        }
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////////////////
+// TESTS GO HERE
+
+#[cfg(test)]
+mod tests {
+    use super::{NuGetVersion as V, NuGetRange as R, NuGetError};
+
+    #[test]
+    fn parsing() {
+        assert_eq!(V::from_str("1.2.3.4").unwrap(), V::new(1,2,3,4));
+        assert_eq!(V::from_str("1").unwrap(), V::new(1,0,0,0));
+        assert_eq!(V::from_str("1.0-beta.2+abc").unwrap().release(), Some("beta.2"));
+        assert_eq!(V::from_str("1.0-beta.2+abc").unwrap().metadata(), Some("abc"));
+        assert!(V::from_str("1.2.3.4.5").is_none());
+        assert!(V::from_str("1.x").is_none());
+        assert!(V::from_str("1.0-").is_none());
+        assert!(V::from_str("1.0-beta..1").is_none());
+        assert_eq!("1.2.3.4".parse::<V>(), Ok(V::new(1,2,3,4)));
+        assert_eq!("1.x".parse::<V>(), Err(NuGetError::InvalidVersion("1.x".to_string())));
+    }
+
+    #[test]
+    fn normalization() {
+        assert_eq!(V::from_str("1.0").unwrap(), V::from_str("1.0.0.0").unwrap());
+        assert_eq!(V::from_str("1.0").unwrap().to_string(), "1.0.0");
+        assert_eq!(V::from_str("01.02.3.0").unwrap().to_string(), "1.2.3");
+        assert_eq!(V::from_str("1.2.3.4-RC+sha").unwrap().to_string(), "1.2.3.4-RC");
+        assert_eq!(V::from_str("1.2.3.4-RC+sha").unwrap().to_full_string(), "1.2.3.4-RC+sha");
+        assert_eq!(V::from_str("1.0-RC"), V::from_str("1.0.0-rc"));
+        assert_eq!(V::from_str("1.0+a"), V::from_str("1.0+b"));
+    }
+
+    #[test]
+    fn ordering() {
+        let mut versions : Vec<V> = ["1.0.0", "1.0.0-rc.10", "1.0.0.1", "1.0.0-rc.2", "1.0.0-beta", "0.9"]
+            .iter().map(|v| V::from_str(v).unwrap()).collect();
+        versions.sort();
+        let rendered : Vec<String> = versions.iter().map(|v| v.to_string()).collect();
+        assert_eq!(rendered, vec!["0.9.0", "1.0.0-beta", "1.0.0-rc.2", "1.0.0-rc.10", "1.0.0", "1.0.0.1"]);
+        assert!(V::from_str("1.0.0-alpha.1").unwrap() < V::from_str("1.0.0-alpha.beta").unwrap());
+    }
+
+    #[test]
+    fn ranges() {
+        let v = |s : &str| V::from_str(s).unwrap();
+
+        assert!(R::from_str("1.0").unwrap().satisfies(&v("1.0")));
+        assert!(R::from_str("1.0").unwrap().satisfies(&v("5.0")));
+        assert!(!R::from_str("1.0").unwrap().satisfies(&v("0.9")));
+        assert!(R::from_str("[1.0,)").unwrap().satisfies(&v("1.0")));
+        assert!(!R::from_str("(1.0,)").unwrap().satisfies(&v("1.0")));
+        assert!(R::from_str("(1.0,2.0]").unwrap().satisfies(&v("2.0")));
+        assert!(!R::from_str("(1.0,2.0)").unwrap().satisfies(&v("2.0")));
+        assert!(R::from_str("(,1.0]").unwrap().satisfies(&v("0.1")));
+        assert!(R::from_str("[1.0]").unwrap().satisfies(&v("1.0.0.0")));
+        assert!(!R::from_str("[1.0]").unwrap().satisfies(&v("1.0.0.1")));
+
+        assert!(R::from_str("(1.0)").is_none());
+        assert_eq!("[1.0,2.0)".parse::<R>().unwrap(), R::from_str("[1.0,2.0)").unwrap());
+        assert_eq!("(1.0)".parse::<R>().unwrap_err().to_string(), "`(1.0)` isn't a valid nuget range");
+        assert!(R::from_str("[2.0,1.0]").is_none());
+        assert!(R::from_str("(,)").is_none());
+        assert!(R::from_str("[1.0,2.0").is_none());
+    }
+
+    #[test]
+    fn floating_ranges() {
+        let v = |s : &str| V::from_str(s).unwrap();
+
+        assert!(R::from_str("*").unwrap().satisfies(&v("12.3")));
+        assert!(R::from_str("1.*").unwrap().satisfies(&v("1.9.2")));
+        assert!(!R::from_str("1.*").unwrap().satisfies(&v("2.0")));
+        assert!(R::from_str("1.2.*").unwrap().satisfies(&v("1.2.7.1")));
+        assert!(!R::from_str("1.2.*").unwrap().satisfies(&v("1.3")));
+        assert!(R::from_str("1.0.0-*").unwrap().satisfies(&v("1.0.0-beta.4")));
+        assert!(R::from_str("1.0.0-beta*").unwrap().satisfies(&v("1.0.0-Beta.4")));
+        assert!(!R::from_str("1.0.0-beta*").unwrap().satisfies(&v("1.0.0-rc")));
+        assert!(!R::from_str("1.0.0-*").unwrap().satisfies(&v("1.0.1-beta")));
+        assert!(R::from_str("1.*.2").is_none());
+
+        // numeric floats only take pre-releases when they float the label too
+        assert!(!R::from_str("1.*").unwrap().satisfies(&v("1.9.0-beta")));
+        assert!(!R::from_str("*").unwrap().satisfies(&v("1.9.0-beta")));
+        assert!(R::from_str("1.*-*").unwrap().satisfies(&v("1.9.0-beta")));
+        assert!(R::from_str("1.*-*").unwrap().satisfies(&v("1.9.0")));
+        assert!(R::from_str("*-*").unwrap().satisfies(&v("3.0-rc")));
+        assert!(R::from_str("1.2.*-rc*").unwrap().satisfies(&v("1.2.5-RC.2")));
+        assert!(!R::from_str("1.2.*-rc*").unwrap().satisfies(&v("1.2.5-beta")));
+        assert!(R::from_str("1.0-beta*-*").is_none());
+        assert!(R::from_str("1.*-rc..*").is_none());
+    }
+
+    #[test]
+    fn best_match() {
+        let versions : Vec<V> = ["1.0", "1.1", "1.4", "2.0"].iter().map(|v| V::from_str(v).unwrap()).collect();
+
+        assert_eq!(R::from_str("1.1").unwrap().best_match(&versions), Some(&versions[1]));
+        assert_eq!(R::from_str("1.*").unwrap().best_match(&versions), Some(&versions[2]));
+        assert_eq!(R::from_str("[3.0,)").unwrap().best_match(&versions), None);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde() {
+        use serde_test::{Token, Configure, assert_tokens};
+
+        let version = V::from_str("1.2.0.4-rc.1+abc").unwrap();
+        assert_tokens(&version.clone().readable(), &[Token::Str("1.2.0.4-rc.1+abc")]);
+        assert_tokens(&version.compact(), &[
+            Token::Tuple { len : 6 },
+            Token::U32(1), Token::U32(2), Token::U32(0), Token::U32(4),
+            Token::Some, Token::Str("rc.1"),
+            Token::Some, Token::Str("abc"),
+            Token::TupleEnd,
+        ]);
+
+        let range = R::from_str("[1.0,2.0)").unwrap();
+        assert_tokens(&range.clone().readable(), &[Token::Str("[1.0.0,2.0.0)")]);
+        assert_tokens(&range.compact(), &[
+            Token::Tuple { len : 6 },
+            Token::Some, Token::Tuple { len : 6 }, Token::U32(1), Token::U32(0), Token::U32(0), Token::U32(0), Token::None, Token::None, Token::TupleEnd,
+            Token::Bool(true),
+            Token::Some, Token::Tuple { len : 6 }, Token::U32(2), Token::U32(0), Token::U32(0), Token::U32(0), Token::None, Token::None, Token::TupleEnd,
+            Token::Bool(false),
+            Token::None,
+            Token::None,
+            Token::TupleEnd,
+        ]);
+
+        let floating = R::from_str("1.2.*").unwrap();
+        assert_tokens(&floating.clone().readable(), &[Token::Str("1.2.*")]);
+        assert_tokens(&floating.compact(), &[
+            Token::Tuple { len : 6 },
+            Token::Some, Token::Tuple { len : 6 }, Token::U32(1), Token::U32(2), Token::U32(0), Token::U32(0), Token::None, Token::None, Token::TupleEnd,
+            Token::Bool(true),
+            Token::None,
+            Token::Bool(false),
+            Token::Some, Token::U8(2),
+            Token::None,
+            Token::TupleEnd,
+        ]);
+
+        let prerelease = R::from_str("1.*-*").unwrap();
+        assert_tokens(&prerelease.compact(), &[
+            Token::Tuple { len : 6 },
+            Token::Some, Token::Tuple { len : 6 }, Token::U32(1), Token::U32(0), Token::U32(0), Token::U32(0), Token::None, Token::None, Token::TupleEnd,
+            Token::Bool(true),
+            Token::None,
+            Token::Bool(false),
+            Token::Some, Token::U8(1),
+            Token::Some, Token::Str(""),
+            Token::TupleEnd,
+        ]);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn invalid_compact_ranges() {
+        use serde_test::{Token, assert_de_tokens_error};
+
+        let version = |major| [Token::Some, Token::Tuple { len : 6 }, Token::U32(major), Token::U32(0), Token::U32(0), Token::U32(0), Token::None, Token::None, Token::TupleEnd];
+        let range = |min : u32, min_inclusive, max : Option<u32>, max_inclusive, float : Option<u8>| {
+            let mut tokens = vec![Token::Tuple { len : 6 }];
+            tokens.extend_from_slice(&version(min));
+            tokens.push(Token::Bool(min_inclusive));
+            match max {
+                Some(max) => tokens.extend_from_slice(&version(max)),
+                None => tokens.push(Token::None),
+            }
+            tokens.push(Token::Bool(max_inclusive));
+            match float {
+                Some(float) => tokens.extend_from_slice(&[Token::Some, Token::U8(float)]),
+                None => tokens.push(Token::None),
+            }
+            tokens.extend_from_slice(&[Token::None, Token::TupleEnd]);
+            tokens
+        };
+
+        assert_de_tokens_error::<serde_test::Compact<R>>(&range(2, true, Some(1), true, None), "`[2.0.0,1.0.0]` isn't a valid nuget range");
+        assert_de_tokens_error::<serde_test::Compact<R>>(&range(1, false, Some(1), false, None), "`[1.0.0]` isn't a valid nuget range");
+        assert_de_tokens_error::<serde_test::Compact<R>>(&range(1, true, Some(2), false, Some(1)), "`1.*` isn't a valid nuget range");
+    }
+
+    #[test]
+    fn range_rendering() {
+        assert_eq!(R::from_str("1.0").unwrap().to_string(), "1.0.0");
+        assert_eq!(R::from_str("[1.0]").unwrap().to_string(), "[1.0.0]");
+        assert_eq!(R::from_str("( 1.0 , 2.0 ]").unwrap().to_string(), "(1.0.0,2.0.0]");
+        assert_eq!(R::from_str("(,2.0]").unwrap().to_string(), "(,2.0.0]");
+        assert_eq!(R::from_str("1.*").unwrap().to_string(), "1.*");
+        assert_eq!(R::from_str("1.0.0-*").unwrap().to_string(), "1.0.0-*");
+        assert_eq!(R::from_str("1.*-*").unwrap().to_string(), "1.*-*");
+        assert_eq!(R::from_str("*-rc*").unwrap().to_string(), "*-rc*");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn hashing() {
+        use std::hash::{Hash, Hasher};
+        use std::collections::hash_map::DefaultHasher;
+
+        let hash = |version : &str| {
+            let mut hasher = DefaultHasher::new();
+            V::from_str(version).unwrap().hash(&mut hasher);
+            hasher.finish()
+        };
+
+        // versions that are equal hash the same
+        assert_eq!(V::from_str("1.0.0-rc.01"), V::from_str("1.0.0-rc.1"));
+        assert_eq!(hash("1.0.0-rc.01"), hash("1.0.0-rc.1"));
+        assert_eq!(hash("1.0-RC.1+abc"), hash("1.0.0.0-rc.1"));
+        assert_ne!(hash("1.0.0-rc.1"), hash("1.0.0-rc.2"));
+    }
+}
@@ -0,0 +1,196 @@
+//! **packing** versions into a single integer, a number of bits for each component.
+
+use core::fmt;
+
+use crate::prelude::*;
+use crate::version::Version;
+use crate::versionpart::VersionPart;
+
+/// why a version couldn't be packed or unpacked
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PackError {
+    /// the component at `index` doesn't fit in its bits
+    Overflow { index : usize, value : u32, bits : u8 },
+    /// the version has more components than the layout
+    TooManyComponents { len : usize, max : usize },
+    /// the layout needs more bits than the integer has
+    TooWide { bits : u32 },
+    /// patterns with a wildcard can't be packed
+    Wildcard,
+    /// pre-releases can't be packed, there is nowhere to keep them before their release
+    PreRelease,
+    /// the packed number has bits set above the layout
+    UnusedBits(u64),
+}
+
+impl fmt::Display for PackError {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PackError::Overflow { index, value, bits } => write!(f, "component {} is {}, which doesn't fit in {} bits", index, value, bits),
+            PackError::TooManyComponents { len, max } => write!(f, "the version has {} components, but only {} can be packed", len, max),
+            PackError::TooWide { bits } => write!(f, "the layout needs {} bits", bits),
+            PackError::Wildcard => write!(f, "a version with a wildcard can't be packed"),
+            PackError::PreRelease => write!(f, "a pre-release version can't be packed"),
+            PackError::UnusedBits(packed) => write!(f, "{:#x} has bits set that aren't in the layout", packed),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PackError { }
+
+/// packs versions into a `u32` or `u64` with a number of bits for each component, the
+/// major in the highest bits, so the numbers sort the same as the versions.
+///
+/// missing components are packed as `0`, and unpacking always gives every component, so
+/// `1.2` comes back as `1.2.0` with `[8, 8, 16]`.
+///
+/// ```
+/// # use version_lp::{Version, VersionPacking};
+/// let windows = VersionPacking::new(&[16; 4]).unwrap();
+/// let version = Version::from_str("10.0.19041.1").unwrap();
+///
+/// let packed = windows.pack_u64(&version).unwrap();
+/// assert_eq!(packed, 0x000A_0000_4A61_0001);
+/// assert_eq!(windows.unpack_u64(packed).unwrap(), version);
+///
+/// let code = VersionPacking::new(&[8, 8, 16]).unwrap();
+/// assert!(code.pack_u32(&Version::from_str("1.256").unwrap()).is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct VersionPacking {
+    /// the bits for each component, from the major
+    bits : Vec<u8>,
+}
+
+impl VersionPacking {
+
+    /// the layout with the bits for each component, `None` if there aren't any components,
+    /// if a component has `0` or more than 32 bits, or if there are more than 64 bits
+    pub fn new(bits : &[u8]) -> Option<VersionPacking> {
+        if bits.is_empty() || bits.iter().any(|bits| *bits == 0 || *bits > 32) { return None; }
+        if bits.iter().map(|bits| *bits as u32).sum::<u32>() > 64 { return None; }
+
+        Some(VersionPacking { bits : bits.to_vec() })
+    }
+
+    /// the bits for each component
+    pub fn bits(&self) -> &[u8] {
+        &self.bits
+    }
+
+    /// the number of bits the layout uses
+    pub fn width(&self) -> u32 {
+        self.bits.iter().map(|bits| *bits as u32).sum()
+    }
+
+    /// packs the version into a `u64`
+    pub fn pack_u64(&self, version : &Version) -> Result<u64, PackError> {
+        if version.has_wildcards() { return Err(PackError::Wildcard); }
+        if version.is_prerelease() { return Err(PackError::PreRelease); }
+        if version.len() > self.bits.len() {
+            return Err(PackError::TooManyComponents { len : version.len(), max : self.bits.len() });
+        }
+
+        let mut packed : u64 = 0;
+        for (index, bits) in self.bits.iter().copied().enumerate() {
+            let value = version.get(index).and_then(VersionPart::number).unwrap_or(0);
+            if value as u64 > mask(bits) { return Err(PackError::Overflow { index, value, bits }); }
+            packed = packed << bits | value as u64;
+        }
+        Ok(packed)
+    }
+
+    /// packs the version into a `u32`, an error if the layout needs more than 32 bits
+    pub fn pack_u32(&self, version : &Version) -> Result<u32, PackError> {
+        let width = self.width();
+        if width > 32 { return Err(PackError::TooWide { bits : width }); }
+
+        self.pack_u64(version).map(|packed| packed as u32)
+    }
+
+    /// the version packed in the `u64`, with every component of the layout
+    pub fn unpack_u64(&self, packed : u64) -> Result<Version, PackError> {
+        let width = self.width();
+        if width < 64 && packed >> width != 0 { return Err(PackError::UnusedBits(packed)); }
+
+        let mut numbers = vec![0; self.bits.len()];
+        let mut rest = packed;
+        for (number, bits) in numbers.iter_mut().zip(self.bits.iter().copied()).rev() {
+            *number = (rest & mask(bits)) as u32;
+            // shifting by the whole width isn't allowed, but then nothing is left anyway
+            rest = rest.checked_shr(bits as u32).unwrap_or(0);
+        }
+        Ok(Version::new(&numbers))
+    }
+
+    /// the version packed in the `u32`, an error if the layout needs more than 32 bits
+    pub fn unpack_u32(&self, packed : u32) -> Result<Version, PackError> {
+        let width = self.width();
+        if width > 32 { return Err(PackError::TooWide { bits : width }); }
+
+        self.unpack_u64(packed as u64)
+    }
+}
+
+/// the largest number that fits in the bits
+fn mask(bits : u8) -> u64 {
+    (1 << bits) - 1
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////////////////
+// TESTS GO HERE
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(version : &str) -> Version {
+        Version::from_str(version).unwrap()
+    }
+
+    #[test]
+    fn layouts() {
+        assert!(VersionPacking::new(&[]).is_none());
+        assert!(VersionPacking::new(&[8, 0, 8]).is_none());
+        assert!(VersionPacking::new(&[33]).is_none());
+        assert!(VersionPacking::new(&[32, 32, 1]).is_none());
+        assert_eq!(VersionPacking::new(&[32, 32]).unwrap().width(), 64);
+
+        let code = VersionPacking::new(&[8, 8, 16]).unwrap();
+        assert_eq!(code.pack_u32(&v("1.2.3")), Ok(0x0102_0003));
+        assert_eq!(code.pack_u32(&v("1.2")), Ok(0x0102_0000));
+        assert_eq!(code.unpack_u32(0x0102_0003).unwrap().to_string(), "1.2.3");
+        assert_eq!(code.unpack_u32(0).unwrap().to_string(), "0.0.0");
+        assert_eq!(code.pack_u64(&v("255.255.65535")), Ok(0xFFFF_FFFF));
+
+        let wide = VersionPacking::new(&[32, 32]).unwrap();
+        assert_eq!(wide.pack_u64(&v("4294967295.1")), Ok(0xFFFF_FFFF_0000_0001));
+        assert_eq!(wide.unpack_u64(u64::MAX).unwrap().to_string(), "4294967295.4294967295");
+    }
+
+    #[test]
+    fn errors() {
+        let code = VersionPacking::new(&[8, 8, 16]).unwrap();
+
+        assert_eq!(code.pack_u32(&v("1.256")), Err(PackError::Overflow { index : 1, value : 256, bits : 8 }));
+        assert_eq!(code.pack_u32(&v("1.2.3.4")), Err(PackError::TooManyComponents { len : 4, max : 3 }));
+        assert_eq!(code.pack_u32(&v("1.*")), Err(PackError::Wildcard));
+        assert_eq!(code.pack_u32(&v("1.2.3-rc.1")), Err(PackError::PreRelease));
+        assert_eq!(code.unpack_u64(0x1_0000_0000), Err(PackError::UnusedBits(0x1_0000_0000)));
+
+        let windows = VersionPacking::new(&[16; 4]).unwrap();
+        assert_eq!(windows.pack_u32(&v("1.2.3.4")), Err(PackError::TooWide { bits : 64 }));
+        assert_eq!(windows.unpack_u32(1), Err(PackError::TooWide { bits : 64 }));
+        assert_eq!(PackError::Overflow { index : 1, value : 256, bits : 8 }.to_string(), "component 1 is 256, which doesn't fit in 8 bits");
+    }
+
+    #[test]
+    fn order() {
+        let windows = VersionPacking::new(&[16; 4]).unwrap();
+        let versions = ["0.0.0.1", "0.1", "1.0.0.0", "1.0.2", "1.2", "1.2.0.1", "1.10", "10.0.19041.1", "65535.0"];
+
+        let packed : Vec<u64> = versions.iter().map(|version| windows.pack_u64(&v(version)).unwrap()).collect();
+        assert!(packed.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
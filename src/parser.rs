@@ -0,0 +1,491 @@
+//! a configurable **parser** for version strings that aren't as clean as `from_str` expects.
+
+use core::fmt;
+use core::str::FromStr;
+
+use crate::prelude::*;
+use crate::version::Version;
+use crate::versionref::VersionRef;
+use crate::versionpart::VersionPart;
+use crate::parts::Parts;
+use crate::lenient::LenientVersion;
+use crate::identifiers::{is_numeric, is_valid_label};
+
+/// how forgiving the parser is with strings that don't follow the rules
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ParseMode {
+    /// the string has to follow all the rules of the parser
+    Strict,
+    /// if the string doesn't follow the rules the version is pulled out of it
+    /// with `LenientVersion`, ignoring any text around it
+    Lenient,
+}
+
+/// why a string couldn't be parsed into a version
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ParseError {
+    /// there was nothing to parse
+    Empty,
+    /// the component at `index` isn't a number or a wildcard
+    InvalidComponent { index : usize, component : String },
+    /// the component at `index` has leading zeros, and they aren't allowed
+    LeadingZero { index : usize, component : String },
+    /// there were less components than the parser's minimum
+    TooFewComponents { min : usize, found : usize },
+    /// there were more components than the parser's maximum
+    TooManyComponents { max : usize, found : usize },
+    /// the version has a pre-release label, and they aren't allowed
+    PreReleaseNotAllowed,
+    /// the version has build metadata, and it isn't allowed
+    BuildNotAllowed,
+    /// the pre-release label isn't made of dot separated alphanumeric identifiers
+    InvalidPreRelease(String),
+    /// the build metadata isn't made of dot separated alphanumeric identifiers
+    InvalidBuild(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "empty version string"),
+            ParseError::InvalidComponent { index, component } => write!(f, "component {} ('{}') is not a number or wildcard", index, component),
+            ParseError::LeadingZero { index, component } => write!(f, "component {} ('{}') has a leading zero", index, component),
+            ParseError::TooFewComponents { min, found } => write!(f, "expected at least {} components, found {}", min, found),
+            ParseError::TooManyComponents { max, found } => write!(f, "expected at most {} components, found {}", max, found),
+            ParseError::PreReleaseNotAllowed => write!(f, "pre-release labels are not allowed"),
+            ParseError::BuildNotAllowed => write!(f, "build metadata is not allowed"),
+            ParseError::InvalidPreRelease(pre) => write!(f, "invalid pre-release label '{}'", pre),
+            ParseError::InvalidBuild(build) => write!(f, "invalid build metadata '{}'", build),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError { }
+
+/// builds up the rules that are used when parsing a version string.
+///
+/// ```
+/// # use version_lp::{Version, VersionParser};
+/// let parser = VersionParser::new()
+///     .allow_prefix(true)
+///     .trim_whitespace(true);
+///
+/// let version = parser.parse(" v1.4.0\n").unwrap();
+/// assert_eq!(version, Version::new(&[1,4,0]));
+/// assert_eq!(version.to_prefixed_string(), "v1.4.0");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VersionParser {
+    separators : Vec<char>,
+    min_components : usize,
+    max_components : Option<usize>,
+    wildcards : Vec<char>,
+    allow_prefix : bool,
+    trim_whitespace : bool,
+    allow_leading_zeros : bool,
+    allow_pre_release : bool,
+    allow_build : bool,
+    mode : ParseMode,
+}
+
+impl Default for VersionParser {
+    fn default() -> VersionParser {
+        //! the same rules as `Version::from_str`
+        VersionParser {
+            separators : vec!['.'],
+            min_components : 1,
+            max_components : None,
+            wildcards : vec!['*'],
+            allow_prefix : false,
+            trim_whitespace : false,
+            allow_leading_zeros : true,
+            allow_pre_release : true,
+            allow_build : true,
+            mode : ParseMode::Strict,
+        }
+    }
+}
+
+impl VersionParser {
+
+    /// creates a parser with the same rules as `Version::from_str`
+    pub fn new() -> VersionParser {
+        VersionParser::default()
+    }
+
+    /// the characters that split the components, `.` by default.
+    ///
+    /// if `-` is a separator it only starts a pre-release label when it isn't
+    /// followed by a number, so `1-2-rc` is `1.2-rc`.
+    pub fn separators(mut self, separators : &[char]) -> VersionParser {
+        self.separators = separators.to_vec();
+        self
+    }
+
+    /// the least number of components the version can have, `1` by default
+    pub fn min_components(mut self, min : usize) -> VersionParser {
+        self.min_components = min;
+        self
+    }
+
+    /// the most number of components the version can have, no limit by default
+    pub fn max_components(mut self, max : usize) -> VersionParser {
+        self.max_components = Some(max);
+        self
+    }
+
+    /// the characters that are treated as wildcards when they are a whole component, `*` by default
+    pub fn wildcards(mut self, wildcards : &[char]) -> VersionParser {
+        self.wildcards = wildcards.to_vec();
+        self
+    }
+
+    /// allows an optional `v` or `V` before the version, i.e. `v1.4.0`. the prefix is
+    /// remembered in the version for `to_prefixed_string`
+    pub fn allow_prefix(mut self, allow : bool) -> VersionParser {
+        self.allow_prefix = allow;
+        self
+    }
+
+    /// ignores whitespace around the version, i.e. `" 1.2.3\n"`
+    pub fn trim_whitespace(mut self, trim : bool) -> VersionParser {
+        self.trim_whitespace = trim;
+        self
+    }
+
+    /// allows numbers written with leading zeros, i.e. `0132.1.2`. this is allowed by
+    /// default, the zeros are dropped when parsed (`0132` is `132`).
+    pub fn allow_leading_zeros(mut self, allow : bool) -> VersionParser {
+        self.allow_leading_zeros = allow;
+        self
+    }
+
+    /// allows a pre-release label after a `-`, i.e. `1.2.3-rc.1`. allowed by default
+    pub fn allow_pre_release(mut self, allow : bool) -> VersionParser {
+        self.allow_pre_release = allow;
+        self
+    }
+
+    /// allows build metadata after a `+`, i.e. `1.2.3+abc`. allowed by default
+    pub fn allow_build(mut self, allow : bool) -> VersionParser {
+        self.allow_build = allow;
+        self
+    }
+
+    /// sets how forgiving the parser is, `ParseMode::Strict` by default
+    pub fn mode(mut self, mode : ParseMode) -> VersionParser {
+        self.mode = mode;
+        self
+    }
+
+    /// parses the string using the rules of the parser.
+    ///
+    /// in `ParseMode::Lenient` the version is only searched for when the string isn't a
+    /// version at all, a version that breaks one of the parser's rules is still an error.
+    /// the version that is found has to follow the rules too, and a wildcard (`*`, `x`) that
+    /// isn't one of the parser's is an error instead of being dropped.
+    pub fn parse(&self, version : &str) -> Result<Version, ParseError> {
+        match self.parse_strict(version) {
+            Ok(version) => Ok(version),
+            Err(error @ ParseError::InvalidComponent { .. }) if self.mode == ParseMode::Lenient => {
+                let lenient = LenientVersion::from_str(version).ok_or(error)?;
+
+                // the version with the text after it, so a label is kept, and then without it.
+                // a wildcard the parser doesn't take is still part of the version, so it isn't cut
+                let found = &version.trim()[lenient.stripped_prefix().map_or(0, str::len) ..];
+                match self.parse_strict(found) {
+                    Err(ParseError::InvalidComponent { component, .. }) if !is_wildcard_like(&component) => {
+                        let end = found.len() - lenient.stripped_suffix().map_or(0, str::len);
+                        self.parse_strict(&found[.. end])
+                    },
+                    result => result,
+                }
+            },
+            Err(error) => Err(error),
+        }
+    }
+
+    /// deserializes a version using the rules of the parser, can be used to write
+    /// a function for `#[serde(deserialize_with = "...")]`
+    #[cfg(feature = "serde")]
+    pub fn deserialize<'de, D>(&self, deserializer : D) -> Result<Version, D::Error> where D : serde::Deserializer<'de> {
+        serde::de::DeserializeSeed::deserialize(self, deserializer)
+    }
+
+    /// parses the string without copying it, the labels borrow from the string.
+    ///
+    /// always parses strictly, `ParseMode::Lenient` needs to rewrite the string so it can't be
+    /// used to get a `VersionRef`.
+    pub fn parse_ref<'a>(&self, version : &'a str) -> Result<VersionRef<'a>, ParseError> {
+        let mut version = if self.trim_whitespace { version.trim() } else { version };
+
+        let mut prefix = None;
+        if self.allow_prefix {
+            if let Some(rest) = version.strip_prefix(['v', 'V']) {
+                prefix = version.chars().next();
+                version = rest;
+            }
+        }
+
+        if version.is_empty() { return Err(ParseError::Empty); }
+
+        let (version, build) = match version.find('+') {
+            Some(i) => (&version[..i], Some(&version[i+1..])),
+            None => (version, None),
+        };
+        let (numbers, pre) = match self.find_pre_release(version) {
+            Some(i) => (&version[..i], Some(&version[i+1..])),
+            None => (version, None),
+        };
+
+        // the components are checked before the labels, so a string that isn't a version at
+        // all is an invalid component and `ParseMode::Lenient` knows to look for one in it
+        let mut parts = Parts::new();
+
+        for (index, section) in numbers.split(&self.separators[..]).enumerate() {
+            if let Some(wildcard) = self.wildcard(section) {
+                parts.push(VersionPart::Wildcard(wildcard));
+
+                // we ignore the rest of the string, so we just stop here
+                break;
+            }
+
+            if !is_numeric(section) {
+                return Err(ParseError::InvalidComponent { index, component : section.to_string() });
+            }
+            if !self.allow_leading_zeros && section.len() > 1 && section.starts_with('0') {
+                return Err(ParseError::LeadingZero { index, component : section.to_string() });
+            }
+            match section.parse::<u32>() {
+                Ok(number) => parts.push(VersionPart::Number(number)),
+                Err(_) => return Err(ParseError::InvalidComponent { index, component : section.to_string() }),
+            }
+        }
+
+        if let Some(pre) = pre {
+            if !self.allow_pre_release { return Err(ParseError::PreReleaseNotAllowed); }
+            if !is_valid_label(pre) { return Err(ParseError::InvalidPreRelease(pre.to_string())); }
+        }
+        if let Some(build) = build {
+            if !self.allow_build { return Err(ParseError::BuildNotAllowed); }
+            if !is_valid_label(build) { return Err(ParseError::InvalidBuild(build.to_string())); }
+        }
+
+        self.check_count(parts.len())?;
+
+        Ok(VersionRef::from_parts(parts, pre, build, prefix))
+    }
+
+    fn parse_strict(&self, version : &str) -> Result<Version, ParseError> {
+        self.parse_ref(version).map(|version| version.to_version())
+    }
+
+    /// finds the `-` that starts the pre-release label
+    fn find_pre_release(&self, version : &str) -> Option<usize> {
+        let dash_is_separator = self.separators.contains(&'-');
+
+        version.match_indices('-')
+            .map(|(i, _)| i)
+            .find(|i| !dash_is_separator || !version[i+1..].starts_with(|c : char| c.is_ascii_digit()))
+    }
+
+    /// the wildcard if the section is a single wildcard character
+    fn wildcard(&self, section : &str) -> Option<char> {
+        let mut chars = section.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if self.wildcards.contains(&c) => Some(c),
+            _ => None,
+        }
+    }
+
+    fn check_count(&self, found : usize) -> Result<(), ParseError> {
+        if found < self.min_components {
+            return Err(ParseError::TooFewComponents { min : self.min_components, found });
+        }
+        if let Some(max) = self.max_components {
+            if found > max { return Err(ParseError::TooManyComponents { max, found }); }
+        }
+        Ok(())
+    }
+}
+
+/// checks if the component is a character that's commonly used as a wildcard
+fn is_wildcard_like(component : &str) -> bool {
+    matches!(component, "*" | "x" | "X")
+}
+
+impl FromStr for Version {
+    type Err = ParseError;
+
+    fn from_str(version : &str) -> Result<Version, ParseError> {
+        //! parses with the default `VersionParser`, same as `Version::from_str`
+        VersionParser::new().parse(version)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl <'de> serde::de::DeserializeSeed<'de> for &VersionParser {
+    type Value = Version;
+
+    fn deserialize<D>(self, deserializer : D) -> Result<Version, D::Error> where D : serde::Deserializer<'de> {
+        deserializer.deserialize_str(ParserVisitor { parser : self })
+    }
+}
+
+#[cfg(feature = "serde")]
+struct ParserVisitor<'p> {
+    parser : &'p VersionParser
+}
+
+#[cfg(feature = "serde")]
+impl <'de, 'p> serde::de::Visitor<'de> for ParserVisitor<'p> {
+    type Value = Version;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("version")
+    }
+
+    fn visit_str<A>(self, string : &str) -> Result<Self::Value, A>
+    where A : serde::de::Error,
+    {
+        self.parser.parse(string).map_err(serde::de::Error::custom)
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////////////////
+// TESTS GO HERE
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_match_from_str() {
+        let parser = VersionParser::new();
+        assert_eq!(parser.parse("1.2.3").unwrap(), Version::new(&[1,2,3]));
+        assert_eq!(parser.parse("0132.1.2").unwrap(), Version::new(&[132,1,2]));
+        assert_eq!(parser.parse("v1.2.3"), Err(ParseError::InvalidComponent { index : 0, component : "v1".to_string() }));
+        assert!(parser.parse(" 1.2.3").is_err());
+        assert_eq!(parser.parse(""), Err(ParseError::Empty));
+        assert_eq!("1.2.3".parse::<Version>().unwrap(), Version::new(&[1,2,3]));
+    }
+
+    #[test]
+    fn prefix() {
+        let parser = VersionParser::new().allow_prefix(true);
+        assert_eq!(parser.parse("v1.4.0").unwrap(), Version::new(&[1,4,0]));
+        assert_eq!(parser.parse("V1.4.0").unwrap().prefix(), Some('V'));
+        assert_eq!(parser.parse("v1.4.0").unwrap().to_prefixed_string(), "v1.4.0");
+        assert_eq!(parser.parse("1.4.0").unwrap().to_prefixed_string(), "1.4.0");
+        assert_eq!(parser.parse("v1.4.0").unwrap().to_string(), "1.4.0");
+        assert_eq!(parser.parse("v1.4.0").unwrap().clone().prefix(), Some('v'));
+        assert_eq!(parser.parse("v"), Err(ParseError::Empty));
+        assert!(parser.parse("vv1.4").is_err());
+    }
+
+    #[test]
+    fn whitespace() {
+        let parser = VersionParser::new().trim_whitespace(true).allow_prefix(true);
+        assert_eq!(parser.parse(" 1.2.3\n").unwrap(), Version::new(&[1,2,3]));
+        assert_eq!(parser.parse("\tv1.2.3 ").unwrap().prefix(), Some('v'));
+        assert!(parser.parse("v 1.2.3").is_err());
+    }
+
+    #[test]
+    fn leading_zeros() {
+        let parser = VersionParser::new().allow_leading_zeros(false);
+        assert_eq!(parser.parse("0132.1.2"), Err(ParseError::LeadingZero { index : 0, component : "0132".to_string() }));
+        assert!(parser.parse("1.02").is_err());
+        assert_eq!(parser.parse("0.0.2").unwrap(), Version::new(&[0,0,2]));
+        assert_eq!(parser.parse("1.*").unwrap(), Version::from_str("1.*").unwrap());
+    }
+
+    #[test]
+    fn separators() {
+        let parser = VersionParser::new().separators(&['.', '_', '-']);
+        assert_eq!(parser.parse("1_2-3").unwrap(), Version::new(&[1,2,3]));
+        assert_eq!(parser.parse("1-2-rc.1").unwrap().pre(), Some("rc.1"));
+        assert_eq!(parser.parse("1-2-rc.1").unwrap(), Version::from_str("1.2-rc.1").unwrap());
+        assert!(VersionParser::new().parse("1_2").is_err());
+    }
+
+    #[test]
+    fn component_counts() {
+        let parser = VersionParser::new().min_components(2).max_components(3);
+        assert_eq!(parser.parse("1"), Err(ParseError::TooFewComponents { min : 2, found : 1 }));
+        assert_eq!(parser.parse("1.2.3.4"), Err(ParseError::TooManyComponents { max : 3, found : 4 }));
+        assert!(parser.parse("1.2").is_ok());
+    }
+
+    #[test]
+    fn wildcards() {
+        let parser = VersionParser::new().wildcards(&['x', 'X', '*']);
+        assert!(parser.parse("1.x").unwrap().has_wildcards());
+        assert!(parser.parse("1.X.X").unwrap().has_wildcards());
+        assert_eq!(parser.parse("1.x").unwrap().to_string(), "1.x");
+        assert!(Version::new(&[1,4]).is_compatible_with(&parser.parse("1.x").unwrap()));
+        assert!(VersionParser::new().parse("1.x").is_err());
+    }
+
+    #[test]
+    fn labels() {
+        let version = VersionParser::new().parse("1.2.3-rc.1+build.5").unwrap();
+        assert_eq!(version.pre(), Some("rc.1"));
+        assert_eq!(version.build(), Some("build.5"));
+        assert_eq!(version.to_string(), "1.2.3-rc.1+build.5");
+
+        let parser = VersionParser::new().allow_pre_release(false).allow_build(false);
+        assert_eq!(parser.parse("1.2.3-rc.1"), Err(ParseError::PreReleaseNotAllowed));
+        assert_eq!(parser.parse("1.2.3+abc"), Err(ParseError::BuildNotAllowed));
+        assert_eq!(VersionParser::new().parse("1.2.3-rc..1"), Err(ParseError::InvalidPreRelease("rc..1".to_string())));
+        assert_eq!(VersionParser::new().parse("1.2.3+"), Err(ParseError::InvalidBuild("".to_string())));
+    }
+
+    #[test]
+    fn lenient_mode() {
+        let parser = VersionParser::new().mode(ParseMode::Lenient);
+        assert_eq!(parser.parse("release-1.2.3.Final").unwrap(), Version::new(&[1,2,3]));
+        assert_eq!(parser.parse("latest"), Err(ParseError::InvalidComponent { index : 0, component : "latest".to_string() }));
+        assert!(parser.clone().min_components(4).parse("release-1.2.3").is_err());
+        assert_eq!(parser.parse("release-1.2.3-rc.1").unwrap().pre(), Some("rc.1"));
+    }
+
+    #[test]
+    fn lenient_mode_keeps_the_rules() {
+        let parser = VersionParser::new().mode(ParseMode::Lenient);
+
+        let labels = parser.clone().allow_pre_release(false).allow_build(false);
+        assert_eq!(labels.parse("1.2.3-rc.1"), Err(ParseError::PreReleaseNotAllowed));
+        assert_eq!(labels.parse("release-1.2.3-rc.1"), Err(ParseError::PreReleaseNotAllowed));
+        assert_eq!(labels.parse("1.2.3+abc"), Err(ParseError::BuildNotAllowed));
+        assert_eq!(parser.parse("1.2.3-rc.1+x y"), Err(ParseError::InvalidBuild("x y".to_string())));
+
+        let zeros = parser.clone().allow_leading_zeros(false);
+        assert_eq!(zeros.parse("release-01.2"), Err(ParseError::LeadingZero { index : 0, component : "01".to_string() }));
+
+        assert_eq!(parser.parse("release-1_2"), Err(ParseError::InvalidComponent { index : 0, component : "1_2".to_string() }));
+        assert_eq!(parser.clone().separators(&['.', '_']).parse("release-1_2").unwrap(), Version::new(&[1,2]));
+
+        let wildcards = parser.clone().wildcards(&['x']);
+        assert_eq!(wildcards.parse("release-1.x").unwrap().to_string(), "1.x");
+        assert_eq!(wildcards.parse("release-1.*"), Err(ParseError::InvalidComponent { index : 1, component : "*".to_string() }));
+        assert_eq!(parser.parse("release-1.2.x"), Err(ParseError::InvalidComponent { index : 2, component : "x".to_string() }));
+        assert_eq!(parser.parse("release-1.2.x86").unwrap(), Version::new(&[1,2]));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn deserialize_with_parser() {
+        use serde::de::IntoDeserializer;
+        use serde::de::value::{StrDeserializer, Error};
+
+        let parser = VersionParser::new().allow_prefix(true);
+        let deserializer : StrDeserializer<Error> = "v1.2.3".into_deserializer();
+        assert_eq!(parser.deserialize(deserializer).unwrap(), Version::new(&[1,2,3]));
+
+        let deserializer : StrDeserializer<Error> = "1.x".into_deserializer();
+        let error = parser.deserialize(deserializer).unwrap_err();
+        assert_eq!(error.to_string(), "component 1 ('x') is not a number or wildcard");
+    }
+}
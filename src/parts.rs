@@ -0,0 +1,107 @@
+//! the storage for the **parts** of a version.
+//!
+//! most versions have 4 or less parts, so those are kept inline and only longer
+//! versions go on the heap.
+
+use core::ops::{Deref, DerefMut};
+use core::iter::FromIterator;
+
+use crate::prelude::*;
+use crate::versionpart::VersionPart;
+
+/// how many parts are kept without allocating
+pub(crate) const INLINE_PARTS : usize = 4;
+
+#[derive(Clone)]
+pub(crate) enum Parts {
+    Inline { len : u8, parts : [VersionPart; INLINE_PARTS] },
+    Heap(Vec<VersionPart>),
+}
+
+impl Parts {
+
+    /// an empty list, doesn't allocate
+    pub(crate) const fn new() -> Parts {
+        Parts::Inline { len : 0, parts : [VersionPart::Number(0); INLINE_PARTS] }
+    }
+
+    /// a list of the first `len` parts of the array, used to make versions in `const`s
+    pub(crate) const fn from_inline(parts : [VersionPart; INLINE_PARTS], len : u8) -> Parts {
+        Parts::Inline { len, parts }
+    }
+
+    /// adds a part to the end, moves to the heap if the inline space is full
+    pub(crate) fn push(&mut self, part : VersionPart) {
+        match self {
+            Parts::Inline { len, parts } if (*len as usize) < INLINE_PARTS => {
+                parts[*len as usize] = part;
+                *len += 1;
+            },
+            Parts::Inline { parts, .. } => {
+                let mut heap = Vec::with_capacity(INLINE_PARTS * 2);
+                heap.extend_from_slice(parts);
+                heap.push(part);
+                *self = Parts::Heap(heap);
+            },
+            Parts::Heap(parts) => parts.push(part),
+        }
+    }
+
+    /// keeps the first `len` parts, does nothing if there are fewer
+    pub(crate) fn truncate(&mut self, new_len : usize) {
+        match self {
+            Parts::Inline { len, .. } => if new_len < *len as usize { *len = new_len as u8 },
+            Parts::Heap(parts) => parts.truncate(new_len),
+        }
+    }
+}
+
+impl Deref for Parts {
+    type Target = [VersionPart];
+
+    fn deref(&self) -> &[VersionPart] {
+        match self {
+            Parts::Inline { len, parts } => &parts[.. *len as usize],
+            Parts::Heap(parts) => parts,
+        }
+    }
+}
+
+impl DerefMut for Parts {
+    fn deref_mut(&mut self) -> &mut [VersionPart] {
+        match self {
+            Parts::Inline { len, parts } => &mut parts[.. *len as usize],
+            Parts::Heap(parts) => parts,
+        }
+    }
+}
+
+impl FromIterator<VersionPart> for Parts {
+    fn from_iter<I : IntoIterator<Item = VersionPart>>(iter : I) -> Parts {
+        let mut parts = Parts::new();
+        for part in iter {
+            parts.push(part);
+        }
+        parts
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////////////////
+// TESTS GO HERE
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spills_to_heap() {
+        let mut parts = Parts::new();
+        for i in 0 .. 4 { parts.push(VersionPart::Number(i)); }
+        assert!(matches!(parts, Parts::Inline { len : 4, .. }));
+
+        parts.push(VersionPart::Number(4));
+        assert!(matches!(parts, Parts::Heap(_)));
+        assert_eq!(parts.len(), 5);
+        assert!(parts.iter().enumerate().all(|(i, part)| *part == VersionPart::Number(i as u32)));
+    }
+}
@@ -0,0 +1,463 @@
+//! a **range** of versions written with comparators, `>=1.2, <2`, which is what the
+//! `req!` macro makes.
+
+use core::fmt;
+use core::cmp::Ordering;
+use core::ops::Bound;
+use core::str::FromStr;
+
+use crate::prelude::*;
+use crate::version::{Version, PreReleasePolicy, Part};
+use crate::versionpart::VersionPart;
+use crate::parser::{VersionParser, ParseError};
+
+/// why a string couldn't be parsed into a range, `index` is the comparator it is in
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RangeError {
+    /// the version of the comparator isn't valid
+    InvalidVersion { index : usize, error : ParseError },
+    /// `*` by itself can't be used with a comparison, `>*`
+    WildcardComparison { index : usize },
+    /// the upper bound of `^` or `~` doesn't fit in a `u32`
+    Overflow { index : usize },
+    /// the lower bound is above the upper bound
+    Inverted,
+}
+
+impl fmt::Display for RangeError {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RangeError::InvalidVersion { index, error } => write!(f, "comparator {} has an invalid version, {}", index, error),
+            RangeError::WildcardComparison { index } => write!(f, "comparator {} compares against `*`", index),
+            RangeError::Overflow { index } => write!(f, "the upper bound of comparator {} is too large", index),
+            RangeError::Inverted => write!(f, "the lower bound is above the upper bound"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RangeError { }
+
+/// the versions between two bounds, written as comma separated comparators that all have to match.
+///
+/// `>=`, `>`, `<=`, `<` and `=` compare the same way as `Version`, so missing components
+/// work like a wildcard: `<=1.2` includes `1.2.9` and `>1.2` starts at `1.3`. `^1.2` is
+/// `>=1.2, <2` (`^0.2` is `>=0.2, <0.3`), `~1.2.3` is `>=1.2.3, <1.3`, and a version by
+/// itself (or with a wildcard, `1.2.*`) is the same as `=`. `*` is every version.
+///
+/// ```
+/// # use version_lp::{Version, VersionRange};
+/// let range = VersionRange::from_str(">=1.2, <2").unwrap();
+///
+/// assert!(range.contains(&Version::new(&[1,4,2])));
+/// assert!(!range.contains(&Version::new(&[2,0,0])));
+/// assert_eq!(VersionRange::from_str("^0.3.1").unwrap().to_string(), ">=0.3.1, <0.4");
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct VersionRange {
+    lower : Bound<Version>,
+    upper : Bound<Version>,
+}
+
+impl fmt::Debug for VersionRange {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        //! prints "VersionRange (>=x.x, <x)"
+        write!(f, "VersionRange ({})", self)
+    }
+}
+
+impl fmt::Display for VersionRange {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        //! prints the comparators, ">=1.2, <2", "=1.2" or "*"
+        match (&self.lower, &self.upper) {
+            (Bound::Unbounded, Bound::Unbounded) => return f.write_str("*"),
+            (Bound::Included(lower), Bound::Included(upper))
+                if lower.components() == upper.components() && lower.pre() == upper.pre() => return write!(f, "={}", lower),
+            _ => (),
+        }
+
+        match &self.lower {
+            Bound::Included(lower) => write!(f, ">={}", lower)?,
+            Bound::Excluded(lower) => write!(f, ">{}", lower)?,
+            Bound::Unbounded => (),
+        }
+        if let (Bound::Included(_) | Bound::Excluded(_), Bound::Included(_) | Bound::Excluded(_)) = (&self.lower, &self.upper) {
+            f.write_str(", ")?;
+        }
+        match &self.upper {
+            Bound::Included(upper) => write!(f, "<={}", upper),
+            Bound::Excluded(upper) => write!(f, "<{}", upper),
+            Bound::Unbounded => Ok(()),
+        }
+    }
+}
+
+impl VersionRange {
+
+    /// creates a range from its bounds, used by `req!`
+    pub(crate) const fn from_bounds(lower : Bound<Version>, upper : Bound<Version>) -> VersionRange {
+        VersionRange { lower, upper }
+    }
+
+    /// parses the comparators, `>=1.2, <2`. `None` if one of them isn't valid or if
+    /// the lower bound is above the upper bound, `str::parse` gives a `RangeError` instead
+    // kept next to `FromStr` so the `Option` api still works, like `Version::from_str`
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(range : &str) -> Option<VersionRange> {
+        range.parse().ok()
+    }
+
+    /// the smallest version in the range
+    pub fn lower(&self) -> Bound<&Version> {
+        self.lower.as_ref()
+    }
+
+    /// the largest version in the range
+    pub fn upper(&self) -> Bound<&Version> {
+        self.upper.as_ref()
+    }
+
+    /// checks if the version is between the bounds, pre-releases included
+    pub fn contains(&self, version : &Version) -> bool {
+        let above = match &self.lower {
+            Bound::Included(lower) => version >= lower,
+            Bound::Excluded(lower) => version > lower,
+            Bound::Unbounded => true,
+        };
+        let below = match &self.upper {
+            Bound::Included(upper) => version <= upper,
+            Bound::Excluded(upper) => version < upper,
+            Bound::Unbounded => true,
+        };
+        above && below
+    }
+
+    /// checks if the version is between the bounds and is picked by the pre-release policy,
+    /// `IfRequested` only matches pre-releases if one of the bounds is a pre-release
+    pub fn matches(&self, version : &Version, policy : PreReleasePolicy) -> bool {
+        let requested = [self.lower(), self.upper()].iter()
+            .any(|bound| matches!(bound, Bound::Included(v) | Bound::Excluded(v) if v.is_prerelease()));
+
+        let allowed = match policy {
+            PreReleasePolicy::Include => true,
+            PreReleasePolicy::Exclude => !version.is_prerelease(),
+            PreReleasePolicy::IfRequested => !version.is_prerelease() || requested,
+        };
+        allowed && self.contains(version)
+    }
+
+    /// the versions in the range that step by the part, `1.2.0, 1.2.1, ..` for the patch of
+    /// `>=1.2, <1.3`. it starts at the first version with that many components that is above
+    /// the lower bound, `>1.2` starts at `1.3.0`, and only the part is increased so it stops
+    /// at the upper bound or when the part would overflow
+    pub fn iter_step(&self, part : Part) -> impl Iterator<Item = Version> + '_ {
+        core::iter::successors(first_step(self.lower(), part), move |version| version.next(part))
+            .take_while(move |version| self.contains(version))
+    }
+}
+
+impl FromStr for VersionRange {
+    type Err = RangeError;
+
+    fn from_str(range : &str) -> Result<VersionRange, RangeError> {
+        //! the tightest of the bounds of each comparator is kept
+        let mut lower = Bound::Unbounded;
+        let mut upper = Bound::Unbounded;
+
+        for (index, comparator) in range.split(',').enumerate() {
+            let (op, version) = split_op(comparator.trim_ascii());
+            let (comparator_lower, comparator_upper) = comparator_bounds(index, op, version)?;
+            lower = tighter(lower, comparator_lower, true);
+            upper = tighter(upper, comparator_upper, false);
+        }
+
+        between(lower, upper)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for VersionRange {
+    fn serialize<S>(&self, serializer : S) -> Result<S::Ok, S::Error> where S : serde::Serializer {
+        //! serializes as the comparators, ">=1.2, <2". formats that aren't human readable
+        //! use a tuple of the two bounds
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serde::Serialize::serialize(&(&self.lower, &self.upper), serializer)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl <'de> serde::Deserialize<'de> for VersionRange {
+    fn deserialize<D>(deserializer : D) -> Result<VersionRange, D::Error> where D : serde::Deserializer<'de> {
+        //! the `RangeError` is the error message
+        use serde::de::Error;
+
+        if deserializer.is_human_readable() {
+            let range = String::deserialize(deserializer)?;
+            return range.parse().map_err(D::Error::custom);
+        }
+
+        let (lower, upper) : (Bound<Version>, Bound<Version>) = serde::Deserialize::deserialize(deserializer)?;
+        if [&lower, &upper].iter().any(|bound| matches!(bound, Bound::Included(v) | Bound::Excluded(v) if v.has_wildcards())) {
+            return Err(D::Error::custom("the bounds of a range can't have wildcards"));
+        }
+        between(lower, upper).map_err(D::Error::custom)
+    }
+}
+
+/// the comparison at the start of a comparator
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Op {
+    Exact,
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+    Caret,
+    Tilde,
+}
+
+/// splits the comparison off the version, no comparison is `Op::Exact`
+pub(crate) const fn split_op(comparator : &str) -> (Op, &str) {
+    let (op, len) = match comparator.as_bytes() {
+        [b'>', b'=', ..] => (Op::GreaterEq, 2),
+        [b'<', b'=', ..] => (Op::LessEq, 2),
+        [b'>', ..] => (Op::Greater, 1),
+        [b'<', ..] => (Op::Less, 1),
+        [b'=', ..] => (Op::Exact, 1),
+        [b'^', ..] => (Op::Caret, 1),
+        [b'~', ..] => (Op::Tilde, 1),
+        _ => (Op::Exact, 0),
+    };
+    (op, comparator.split_at(len).1.trim_ascii())
+}
+
+/// the component that `^` increases for the upper bound, the first one that isn't `0`
+pub(crate) const fn caret_index(numbers : &[u32]) -> usize {
+    let mut index = 0;
+    while index + 1 < numbers.len() && numbers[index] == 0 { index += 1; }
+    index
+}
+
+/// the component that `~` increases for the upper bound, the minor if there is one
+pub(crate) const fn tilde_index(numbers : &[u32]) -> usize {
+    if numbers.len() > 1 { 1 } else { 0 }
+}
+
+/// checks if the first bound is tighter than the second, `ordering` is the first version
+/// compared to the second, with the length and if the bound is excluded for each.
+///
+/// versions that are equal because one is shorter (`1.2` and `1.2.5`) are decided by how
+/// they are bound, `>1.2` is tighter than `>=1.2.5`, which is tighter than `>=1.2`.
+pub(crate) const fn is_tighter(ordering : Ordering, lower : bool, first : (usize, bool), second : (usize, bool)) -> bool {
+    let ((first_len, first_excluded), (second_len, second_excluded)) = (first, second);
+
+    match ordering {
+        Ordering::Greater => lower,
+        Ordering::Less => !lower,
+        Ordering::Equal if first_len < second_len => first_excluded,
+        Ordering::Equal if first_len > second_len => !second_excluded,
+        Ordering::Equal => first_excluded && !second_excluded,
+    }
+}
+
+/// the bounds of one comparator, the one at `index`
+fn comparator_bounds(index : usize, op : Op, version : &str) -> Result<(Bound<Version>, Bound<Version>), RangeError> {
+    let mut version = VersionParser::new().parse(version).map_err(|error| RangeError::InvalidVersion { index, error })?;
+    if version.has_wildcards() {
+        // `*` is every version, and can't be used with a comparison
+        if version.len() == 1 {
+            return match op {
+                Op::Exact => Ok((Bound::Unbounded, Bound::Unbounded)),
+                _ => Err(RangeError::WildcardComparison { index }),
+            };
+        }
+        let len = version.len() - 1;
+        version.truncate(len);
+    }
+
+    Ok(match op {
+        Op::Exact => (Bound::Included(version.clone()), Bound::Included(version)),
+        Op::Greater => (Bound::Excluded(version), Bound::Unbounded),
+        Op::GreaterEq => (Bound::Included(version), Bound::Unbounded),
+        Op::Less => (Bound::Unbounded, Bound::Excluded(version)),
+        Op::LessEq => (Bound::Unbounded, Bound::Included(version)),
+        Op::Caret | Op::Tilde => {
+            let numbers : Vec<u32> = version.parts().filter_map(VersionPart::number).collect();
+            let bumped = match op {
+                Op::Caret => caret_index(&numbers),
+                _ => tilde_index(&numbers),
+            };
+            let upper = bump(&numbers, bumped).ok_or(RangeError::Overflow { index })?;
+            (Bound::Included(version), Bound::Excluded(upper))
+        },
+    })
+}
+
+/// the first version with the part's components at or above the lower bound
+fn first_step(lower : Bound<&Version>, part : Part) -> Option<Version> {
+    let len = part.index() + 1;
+    let (version, excluded) = match lower {
+        Bound::Included(version) => (version, false),
+        Bound::Excluded(version) => (version, true),
+        Bound::Unbounded => return Some(Version::new(&vec![0; len])),
+    };
+
+    let mut numbers : Vec<u32> = version.parts().filter_map(VersionPart::number).collect();
+    // a release is above its pre-releases, so only an excluded release needs stepping past
+    let mut past = excluded && !version.is_prerelease();
+    if numbers.len() > len {
+        past |= numbers[len ..].iter().any(|number| *number != 0);
+        numbers.truncate(len);
+    }
+
+    if past {
+        let last = numbers.len() - 1;
+        numbers[last] = numbers[last].checked_add(1)?;
+    }
+    numbers.resize(len, 0);
+    Some(Version::new(&numbers))
+}
+
+/// the numbers up to the index, with the last one increased
+fn bump(numbers : &[u32], index : usize) -> Option<Version> {
+    let mut bumped = numbers[..= index].to_vec();
+    bumped[index] = bumped[index].checked_add(1)?;
+    Some(Version::new(&bumped))
+}
+
+/// the range between the bounds, as long as the lower bound isn't above the upper bound
+fn between(lower : Bound<Version>, upper : Bound<Version>) -> Result<VersionRange, RangeError> {
+    if let (Bound::Included(lower) | Bound::Excluded(lower), Bound::Included(upper) | Bound::Excluded(upper)) = (&lower, &upper) {
+        if lower > upper { return Err(RangeError::Inverted); }
+    }
+    Ok(VersionRange { lower, upper })
+}
+
+/// the tighter of the two lower (or upper) bounds
+fn tighter(current : Bound<Version>, new : Bound<Version>, lower : bool) -> Bound<Version> {
+    let new_is_tighter = match (&current, &new) {
+        (_, Bound::Unbounded) => false,
+        (Bound::Unbounded, _) => true,
+        (Bound::Included(c) | Bound::Excluded(c), Bound::Included(n) | Bound::Excluded(n)) => is_tighter(
+            n.cmp(c), lower,
+            (n.len(), matches!(new, Bound::Excluded(_))),
+            (c.len(), matches!(current, Bound::Excluded(_))),
+        ),
+    };
+
+    if new_is_tighter { new } else { current }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////////////////
+// TESTS GO HERE
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comparators() {
+        let range = |r : &str| VersionRange::from_str(r).map(|range| range.to_string());
+
+        assert_eq!(range(">=1.2, <2"), Some(">=1.2, <2".to_string()));
+        assert_eq!(range(" >= 1.2 ,< 2 "), Some(">=1.2, <2".to_string()));
+        assert_eq!(range("^1.2.3"), Some(">=1.2.3, <2".to_string()));
+        assert_eq!(range("^0.0"), Some(">=0.0, <0.1".to_string()));
+        assert_eq!(range("~1"), Some(">=1, <2".to_string()));
+        assert_eq!(range("1.2.*"), Some("=1.2".to_string()));
+        assert_eq!(range("*"), Some("*".to_string()));
+        assert_eq!(range("<=3-rc.1"), Some("<=3-rc.1".to_string()));
+
+        // the tighter bound is kept
+        assert_eq!(range(">=1.2, >=1.2.5, <3, <=2.4"), Some(">=1.2.5, <=2.4".to_string()));
+        assert_eq!(range(">1.2, >=1.2.5"), Some(">1.2".to_string()));
+
+        assert_eq!(range(""), None);
+        assert_eq!(range(">=2, <1"), None);
+        assert_eq!(range(">*"), None);
+        assert_eq!(range("^4294967295"), None);
+        assert_eq!(range("=>1"), None);
+    }
+
+    #[test]
+    fn errors() {
+        let error = |r : &str| r.parse::<VersionRange>().unwrap_err();
+
+        assert_eq!(error(""), RangeError::InvalidVersion { index : 0, error : ParseError::Empty });
+        assert_eq!(error(">=1, <2.x"), RangeError::InvalidVersion { index : 1, error : ParseError::InvalidComponent { index : 1, component : "x".to_string() } });
+        assert_eq!(error("1, >*"), RangeError::WildcardComparison { index : 1 });
+        assert_eq!(error("~4294967295"), RangeError::Overflow { index : 0 });
+        assert_eq!(error(">=2, <1"), RangeError::Inverted);
+        assert_eq!(error(">*").to_string(), "comparator 0 compares against `*`");
+        assert_eq!(">=1.2, <2".parse::<VersionRange>().unwrap().to_string(), ">=1.2, <2");
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde() {
+        use serde_test::{Token, Configure, assert_tokens, assert_de_tokens_error};
+
+        let range = VersionRange::from_str("^1.2").unwrap();
+        assert_tokens(&range.clone().readable(), &[Token::Str(">=1.2, <2")]);
+        assert_tokens(&VersionRange::from_str("*").unwrap().readable(), &[Token::Str("*")]);
+        assert_de_tokens_error::<serde_test::Readable<VersionRange>>(&[Token::Str(">=2, <1")],
+            "the lower bound is above the upper bound");
+
+        // the compact form is the two bounds, with the versions in their compact form
+        let range_tokens = |lower : &[u32], upper : &[u32], wildcard : bool| {
+            let mut tokens = vec![Token::Tuple { len : 2 }];
+            for (variant, numbers) in [("Included", lower), ("Excluded", upper)] {
+                tokens.extend_from_slice(&[Token::NewtypeVariant { name : "Bound", variant }, Token::Tuple { len : 4 }, Token::Seq { len : Some(numbers.len()) }]);
+                tokens.extend(numbers.iter().map(|n| Token::U32(*n)));
+                tokens.push(Token::SeqEnd);
+                if wildcard { tokens.extend_from_slice(&[Token::Some, Token::Str("*")]); } else { tokens.push(Token::None); }
+                tokens.extend_from_slice(&[Token::None, Token::None, Token::TupleEnd]);
+            }
+            tokens.push(Token::TupleEnd);
+            tokens
+        };
+
+        assert_tokens(&range.compact(), &range_tokens(&[1, 2], &[2], false));
+        assert_de_tokens_error::<serde_test::Compact<VersionRange>>(&range_tokens(&[2], &[1], false),
+            "the lower bound is above the upper bound");
+        assert_de_tokens_error::<serde_test::Compact<VersionRange>>(&range_tokens(&[1], &[2], true),
+            "the bounds of a range can't have wildcards");
+    }
+
+    #[test]
+    fn contains() {
+        let range = VersionRange::from_str(">1.2, <=2.4").unwrap();
+        let contains = |v : &str| range.contains(&Version::from_str(v).unwrap());
+
+        assert!(contains("1.3.0"));
+        assert!(contains("2.4.9"));
+        assert!(!contains("1.2.9"));
+        assert!(!contains("2.5"));
+        assert!(contains("2.0.0-rc.1"));
+
+        let stable = |v : &str, policy| range.matches(&Version::from_str(v).unwrap(), policy);
+        assert!(!stable("2.0.0-rc.1", PreReleasePolicy::IfRequested));
+        assert!(stable("2.0.0-rc.1", PreReleasePolicy::Include));
+        assert!(VersionRange::from_str(">=2.0.0-rc.1").unwrap().matches(&Version::from_str("2.0.0-rc.2").unwrap(), PreReleasePolicy::IfRequested));
+    }
+
+    #[test]
+    fn steps() {
+        let steps = |r : &str, part, n| -> Vec<String> {
+            VersionRange::from_str(r).unwrap().iter_step(part).take(n).map(|v| v.to_string()).collect()
+        };
+
+        assert_eq!(steps(">=1.2, <1.2.3", Part::Patch, 10), vec!["1.2.0", "1.2.1", "1.2.2"]);
+        assert_eq!(steps(">1.2, <=1.5", Part::Minor, 10), vec!["1.3", "1.4", "1.5"]);
+        assert_eq!(steps(">1.2.3", Part::Patch, 2), vec!["1.2.4", "1.2.5"]);
+        assert_eq!(steps(">=1.2.5, <3", Part::Minor, 2), vec!["1.3", "1.4"]);
+        assert_eq!(steps(">=1.2.0, <3", Part::Minor, 2), vec!["1.2", "1.3"]);
+        assert_eq!(steps(">=2.0.0-rc.1, <4", Part::Major, 10), vec!["2", "3"]);
+        assert_eq!(steps("<0.0.2", Part::Patch, 10), vec!["0.0.0", "0.0.1"]);
+        assert_eq!(steps(">=1.4294967295", Part::Minor, 10), vec!["1.4294967295"]);
+        assert!(steps(">1.4294967295", Part::Minor, 10).is_empty());
+    }
+}
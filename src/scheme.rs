@@ -0,0 +1,118 @@
+//! the **VersionScheme** trait, the common interface of all the version formats.
+//!
+//! the plain dotted `Version` is the default scheme, other formats (like `NuGetVersion`)
+//! implement the same trait so the selection helpers work the same on all of them.
+
+use core::cmp::Ordering;
+
+use crate::prelude::*;
+use crate::version::Version;
+use crate::index::index_cmp;
+use crate::nuget::{NuGetVersion, NuGetRange};
+
+pub trait VersionScheme : Sized {
+    /// what versions are checked against, i.e. `1.*` or `[1.0,2.0)`
+    type Requirement;
+
+    /// parses a version string, `None` if it isn't valid for this scheme
+    fn parse(version : &str) -> Option<Self>;
+
+    /// renders the version in its canonical form
+    fn render(&self) -> String;
+
+    /// parses a requirement string, `None` if it isn't valid for this scheme
+    fn parse_requirement(requirement : &str) -> Option<Self::Requirement>;
+
+    /// checks if the version matches the requirement
+    fn is_compatible_with(&self, requirement : &Self::Requirement) -> bool;
+
+    /// a total order of the versions, used for sorting and picking the latest. this isn't
+    /// always `Ord`, `Version` compares the components both versions have so `1.2 == 1.2.0`
+    /// and `1.2 == 1.2.1`, but `1.2.0 < 1.2.1`. that isn't transitive, so it can't sort
+    fn scheme_cmp(&self, other : &Self) -> Ordering;
+
+    /// returns the largest version in the list that is compatible with the requirement
+    fn latest_compatible<'a, I>(requirement : &Self::Requirement, list : I) -> Option<&'a Self>
+        where I : IntoIterator<Item = &'a Self>, Self : 'a
+    {
+        list.into_iter()
+            .filter(|version| version.is_compatible_with(requirement))
+            .max_by(|a, b| a.scheme_cmp(b))
+    }
+
+    /// parses all the strings, skipping the ones that aren't valid, and returns
+    /// the versions sorted from smallest to largest
+    fn parse_sorted<I>(list : I) -> Vec<Self>
+        where I : IntoIterator, I::Item : AsRef<str>
+    {
+        let mut versions : Vec<Self> = list.into_iter()
+            .filter_map(|version| Self::parse(version.as_ref()))
+            .collect();
+        versions.sort_by(Self::scheme_cmp);
+        versions
+    }
+}
+
+impl VersionScheme for Version {
+    type Requirement = Version;
+
+    fn parse(version : &str) -> Option<Version> { Version::from_str(version) }
+    fn render(&self) -> String { self.to_string() }
+    fn parse_requirement(requirement : &str) -> Option<Version> { Version::from_str(requirement) }
+    fn is_compatible_with(&self, requirement : &Version) -> bool { Version::is_compatible_with(self, requirement) }
+    fn scheme_cmp(&self, other : &Version) -> Ordering { index_cmp(self, other) }
+}
+
+impl VersionScheme for NuGetVersion {
+    type Requirement = NuGetRange;
+
+    fn parse(version : &str) -> Option<NuGetVersion> { NuGetVersion::from_str(version) }
+    fn render(&self) -> String { self.to_normalized_string() }
+    fn parse_requirement(requirement : &str) -> Option<NuGetRange> { NuGetRange::from_str(requirement) }
+    fn is_compatible_with(&self, requirement : &NuGetRange) -> bool { requirement.satisfies(self) }
+    fn scheme_cmp(&self, other : &NuGetVersion) -> Ordering { self.cmp(other) }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////////////////
+// TESTS GO HERE
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a generic helper, the kind of thing the trait is for
+    fn latest_rendered<S : VersionScheme>(requirement : &str, list : &[&str]) -> Option<String> {
+        let requirement = S::parse_requirement(requirement)?;
+        let versions = S::parse_sorted(list);
+        S::latest_compatible(&requirement, &versions).map(S::render)
+    }
+
+    #[test]
+    fn dotted_scheme() {
+        let list = ["1.0.0", "1.2.0", "x.y", "2.0.1", "1.1.9"];
+        assert_eq!(latest_rendered::<Version>("1.*", &list), Some("1.2.0".to_string()));
+        assert_eq!(latest_rendered::<Version>("*", &list), Some("2.0.1".to_string()));
+        assert_eq!(latest_rendered::<Version>("3", &list), None);
+    }
+
+    #[test]
+    fn nuget_scheme() {
+        let list = ["1.0", "1.2.0.0", "x.y", "2.0.1", "1.1.9-beta"];
+        assert_eq!(latest_rendered::<NuGetVersion>("[1.0,2.0)", &list), Some("1.2.0".to_string()));
+        assert_eq!(latest_rendered::<NuGetVersion>("1.1", &list), Some("2.0.1".to_string()));
+        assert_eq!(latest_rendered::<NuGetVersion>("(,1.0)", &list), None);
+    }
+
+    #[test]
+    fn parse_sorted() {
+        let versions = NuGetVersion::parse_sorted(vec!["1.0.1", "1.0.0-rc", "nope", "1.0"]);
+        let rendered : Vec<String> = versions.iter().map(VersionScheme::render).collect();
+        assert_eq!(rendered, vec!["1.0.0-rc", "1.0.0", "1.0.1"]);
+
+        // the shorter version first, the same as `VersionIndex`
+        let versions = Version::parse_sorted(vec!["1.2.0", "1.10", "1.2", "1.2.1", "1.2.0-rc"]);
+        let rendered : Vec<String> = versions.iter().map(VersionScheme::render).collect();
+        assert_eq!(rendered, vec!["1.2", "1.2.0-rc", "1.2.0", "1.2.1", "1.10"]);
+        assert_eq!(latest_rendered::<Version>("1.2", &["1.2.1", "1.2", "1.2.0"]), Some("1.2.1".to_string()));
+    }
+}
@@ -0,0 +1,199 @@
+//! **selection** helpers that work on any iterator, for lists of things that have a version
+//! in them (like a release with a url) instead of lists of versions.
+
+use crate::prelude::*;
+use crate::version::{Version, PreReleasePolicy};
+use crate::versionpart::VersionPart;
+use crate::index::index_cmp;
+
+/// iterator adapters for picking items by their version. `key` gets the version out of
+/// an item, and the items themselves are returned, so iterating over references gives
+/// back references to the original items.
+///
+/// ```
+/// # use version_lp::{Version, VersionSelect, PreReleasePolicy};
+/// struct Release { version : Version, url : &'static str }
+///
+/// let releases = vec![
+///     Release { version : Version::new(&[1,0,0]), url : "a" },
+///     Release { version : Version::new(&[1,4,2]), url : "b" },
+///     Release { version : Version::new(&[2,0,0]), url : "c" },
+/// ];
+///
+/// let requirement = Version::from_str("1.*").unwrap();
+/// let latest = releases.iter()
+///     .select_latest_by_key(&requirement, PreReleasePolicy::default(), |r| &r.version)
+///     .unwrap();
+/// assert_eq!(latest.url, "b");
+/// ```
+pub trait VersionSelect : Iterator + Sized {
+
+    /// the item with the largest version that is compatible with the requirement, compared
+    /// the way `VersionIndex` sorts so `1.2.1` is after `1.2`
+    fn select_latest_by_key<F>(self, requirement : &Version, policy : PreReleasePolicy, key : F) -> Option<Self::Item>
+        where F : Fn(&Self::Item) -> &Version
+    {
+        self.filter(|item| requirement.selects(key(item), policy))
+            .max_by(|a, b| index_cmp(key(a), key(b)))
+    }
+
+    /// the item with the smallest version that is compatible with the requirement
+    fn select_oldest_by_key<F>(self, requirement : &Version, policy : PreReleasePolicy, key : F) -> Option<Self::Item>
+        where F : Fn(&Self::Item) -> &Version
+    {
+        self.filter(|item| requirement.selects(key(item), policy))
+            .min_by(|a, b| index_cmp(key(a), key(b)))
+    }
+
+    /// only the items with a version that is compatible with the requirement
+    fn filter_matching<F>(self, requirement : &Version, policy : PreReleasePolicy, key : F) -> FilterMatching<'_, Self, F>
+        where F : Fn(&Self::Item) -> &Version
+    {
+        FilterMatching { iter : self, requirement, policy, key }
+    }
+
+    /// groups the items by the versions that are compatible with each other under the semver
+    /// caret rule, the same major, or the same minor for `0.x` and the same patch for `0.0.x`.
+    ///
+    /// each group is keyed by the shared part of the version (`1`, `0.3` or `0.0.4`) and
+    /// keeps the order of the items, the groups are sorted by their key. items with a
+    /// wildcard version are skipped.
+    fn group_by_compat_bucket<F>(self, key : F) -> Vec<(Version, Vec<Self::Item>)>
+        where F : Fn(&Self::Item) -> &Version
+    {
+        let mut groups : Vec<(Version, Vec<Self::Item>)> = Vec::new();
+
+        for item in self {
+            let bucket = match compat_bucket(key(&item)) {
+                Some(bucket) => bucket,
+                None => continue,
+            };
+            match groups.iter_mut().find(|(group, _)| group.components() == bucket.components()) {
+                Some((_, items)) => items.push(item),
+                None => groups.push((bucket, vec![item])),
+            }
+        }
+
+        groups.sort_by(|a, b| index_cmp(&a.0, &b.0));
+        groups
+    }
+}
+
+impl <I : Iterator> VersionSelect for I { }
+
+/// the iterator from `VersionSelect::filter_matching`
+pub struct FilterMatching<'r, I, F> {
+    iter : I,
+    requirement : &'r Version,
+    policy : PreReleasePolicy,
+    key : F,
+}
+
+impl <'r, I, F> Iterator for FilterMatching<'r, I, F>
+    where I : Iterator, F : Fn(&I::Item) -> &Version
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        let (requirement, policy, key) = (self.requirement, self.policy, &self.key);
+        self.iter.find(|item| requirement.selects(key(item), policy))
+    }
+}
+
+/// the caret bucket of the version, missing components are counted as `0`
+fn compat_bucket(version : &Version) -> Option<Version> {
+    if version.has_wildcards() { return None; }
+
+    let number = |i : usize| match version.components().get(i) {
+        Some(VersionPart::Number(number)) => *number,
+        _ => 0,
+    };
+
+    Some(match (number(0), number(1)) {
+        (0, 0) => Version::new(&[0, 0, number(2)]),
+        (0, minor) => Version::new(&[0, minor]),
+        (major, _) => Version::new(&[major]),
+    })
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////////////////
+// TESTS GO HERE
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Release {
+        version : Version,
+        url : String,
+    }
+
+    fn releases() -> Vec<Release> {
+        ["1.0.0", "1.4.2", "1.5.0-rc.1", "2.0.0", "0.3.1", "0.3.9", "0.0.4", "0.1", "x.y", "1.*"].iter()
+            .filter_map(|v| Version::from_str(v))
+            .map(|version| Release { url : format!("https://example.com/{}", version.to_string()), version })
+            .collect()
+    }
+
+    #[test]
+    fn select_by_key() {
+        let releases = releases();
+        let requirement = Version::from_str("1.*").unwrap();
+
+        let latest = releases.iter().select_latest_by_key(&requirement, PreReleasePolicy::default(), |r| &r.version).unwrap();
+        assert_eq!(latest.url, "https://example.com/1.4.2");
+        assert!(core::ptr::eq(latest, &releases[1]));
+
+        let latest = releases.iter().select_latest_by_key(&requirement, PreReleasePolicy::Include, |r| &r.version).unwrap();
+        assert_eq!(latest.version.to_string(), "1.5.0-rc.1");
+
+        let oldest = releases.iter().select_oldest_by_key(&requirement, PreReleasePolicy::default(), |r| &r.version).unwrap();
+        assert_eq!(oldest.version.to_string(), "1.0.0");
+
+        let requirement = Version::new(&[3]);
+        assert!(releases.iter().select_latest_by_key(&requirement, PreReleasePolicy::Include, |r| &r.version).is_none());
+
+        // `1.2` and `1.2.1` share a depth, but the longer one is still the latest
+        let requirement = Version::from_str("1.*").unwrap();
+        for list in [[Version::new(&[1,2,1]), Version::new(&[1,2])], [Version::new(&[1,2]), Version::new(&[1,2,1])]] {
+            let latest = list.iter().select_latest_by_key(&requirement, PreReleasePolicy::default(), |v| v).unwrap();
+            assert_eq!(latest.to_string(), "1.2.1");
+            let oldest = list.iter().select_oldest_by_key(&requirement, PreReleasePolicy::default(), |v| v).unwrap();
+            assert_eq!(oldest.to_string(), "1.2");
+        }
+    }
+
+    #[test]
+    fn filter_matching() {
+        let releases = releases();
+        let requirement = Version::from_str("0.3").unwrap();
+
+        let urls : Vec<&str> = releases.iter()
+            .filter_matching(&requirement, PreReleasePolicy::default(), |r| &r.version)
+            .map(|r| r.url.as_str())
+            .collect();
+        assert_eq!(urls, vec!["https://example.com/0.3.1", "https://example.com/0.3.9"]);
+
+        // plain versions work too
+        let versions : Vec<Version> = releases.into_iter().map(|r| r.version).collect();
+        assert_eq!(versions.iter().filter_matching(&requirement, PreReleasePolicy::default(), |v| v).count(), 2);
+    }
+
+    #[test]
+    fn group_by_compat_bucket() {
+        let releases = releases();
+        let groups = releases.iter().group_by_compat_bucket(|r| &r.version);
+
+        let rendered : Vec<(String, Vec<String>)> = groups.iter()
+            .map(|(bucket, items)| (bucket.to_string(), items.iter().map(|r| r.version.to_string()).collect()))
+            .collect();
+
+        assert_eq!(rendered, vec![
+            ("0.0.4".to_string(), vec!["0.0.4".to_string()]),
+            ("0.1".to_string(), vec!["0.1".to_string()]),
+            ("0.3".to_string(), vec!["0.3.1".to_string(), "0.3.9".to_string()]),
+            ("1".to_string(), vec!["1.0.0".to_string(), "1.4.2".to_string(), "1.5.0-rc.1".to_string()]),
+            ("2".to_string(), vec!["2.0.0".to_string()]),
+        ]);
+    }
+}
@@ -0,0 +1,492 @@
+//! the different **serde** representations of a `Version`.
+//!
+//! `Version` serializes as a dotted string (`"1.2.3"`) by default, the modules here can be
+//! used with `#[serde(with = "...")]` to pick another representation. no matter which
+//! representation is used to serialize, deserializing accepts all of them.
+//!
+//! formats that aren't human readable (`Serializer::is_human_readable`) always use a
+//! compact tuple of the component numbers and labels, whatever module is picked.
+//!
+//! ```
+//! # use version_lp::Version;
+//! # use serde::{Serialize, Deserialize};
+//! #[derive(Serialize, Deserialize)]
+//! struct Package {
+//!     version : Version,
+//!     #[serde(with = "version_lp::serde_format::seq")]
+//!     minimum : Version,
+//!     #[serde(with = "version_lp::serde_format::map")]
+//!     latest : Version,
+//! }
+//! ```
+
+use core::fmt;
+
+use crate::prelude::*;
+use serde::{Serializer, Deserializer};
+use serde::ser::{Error as SerError, SerializeSeq, SerializeStruct, SerializeTuple};
+use serde::de::{self, Visitor, MapAccess, SeqAccess};
+
+use crate::version::Version;
+use crate::versionref::VersionRef;
+use crate::versionpart::VersionPart;
+use crate::parts::Parts;
+use crate::parser::VersionParser;
+use crate::identifiers::is_valid_label;
+
+/// the field names used by the `map` representation
+const FIELDS : [&str; 5] = [ "major", "minor", "patch", "pre", "build" ];
+
+/// checks if the wildcard isn't `*`, the only one the default parser reads back
+pub(crate) fn has_custom_wildcard(parts : &[VersionPart]) -> bool {
+    matches!(parts.last(), Some(VersionPart::Wildcard(wildcard)) if *wildcard != '*')
+}
+
+/// the version with its wildcard written as `*`, so versions parsed with other wildcards
+/// can be deserialized again
+fn with_default_wildcard(version : &Version) -> Option<Version> {
+    if !has_custom_wildcard(version.components()) { return None; }
+
+    let parts : Parts = version.components().iter()
+        .map(|part| if part.is_wildcard() { VersionPart::Wildcard('*') } else { *part })
+        .collect();
+    Some(Version::from_parts(parts, version.pre().map(String::from), version.build().map(String::from)))
+}
+
+/// a dotted string, `"1.2.3-rc.1"`. this is the default representation
+pub mod dotted {
+    use super::*;
+
+    pub fn serialize<S>(version : &Version, serializer : S) -> Result<S::Ok, S::Error> where S : Serializer {
+        if !serializer.is_human_readable() { return compact::serialize(version, serializer); }
+        let canonical = with_default_wildcard(version);
+        serializer.serialize_str(&canonical.as_ref().unwrap_or(version).to_string())
+    }
+
+    pub fn deserialize<'de, D>(deserializer : D) -> Result<Version, D::Error> where D : Deserializer<'de> {
+        super::deserialize(deserializer)
+    }
+}
+
+/// an underscore string, `"1_2_3-rc.1"`. the default representation in older versions of the library
+pub mod underscore {
+    use super::*;
+
+    pub fn serialize<S>(version : &Version, serializer : S) -> Result<S::Ok, S::Error> where S : Serializer {
+        if !serializer.is_human_readable() { return compact::serialize(version, serializer); }
+        let canonical = with_default_wildcard(version);
+        serializer.serialize_str(&canonical.as_ref().unwrap_or(version).to_string_serializer())
+    }
+
+    pub fn deserialize<'de, D>(deserializer : D) -> Result<Version, D::Error> where D : Deserializer<'de> {
+        super::deserialize(deserializer)
+    }
+}
+
+/// a map, `{ major = 1, minor = 2, patch = 3, pre = "rc.1" }`.
+///
+/// fields the version doesn't have are left out. versions with more than three
+/// components can't be serialized this way.
+pub mod map {
+    use super::*;
+
+    pub fn serialize<S>(version : &Version, serializer : S) -> Result<S::Ok, S::Error> where S : Serializer {
+        if !serializer.is_human_readable() { return compact::serialize(version, serializer); }
+
+        let parts = version.components();
+        if parts.len() > 3 {
+            return Err(S::Error::custom(format!("{} has more than 3 components", version.to_string())));
+        }
+
+        let len = parts.len() + version.pre().is_some() as usize + version.build().is_some() as usize;
+        let mut state = serializer.serialize_struct("Version", len)?;
+        for (i, part) in parts.iter().enumerate() {
+            state.serialize_field(FIELDS[i], part)?;
+        }
+        if let Some(pre) = version.pre() {
+            state.serialize_field("pre", pre)?;
+        }
+        if let Some(build) = version.build() {
+            state.serialize_field("build", build)?;
+        }
+        state.end()
+    }
+
+    pub fn deserialize<'de, D>(deserializer : D) -> Result<Version, D::Error> where D : Deserializer<'de> {
+        super::deserialize(deserializer)
+    }
+}
+
+/// a sequence of the components, `[1, 2, 3]`, wildcards are written as `"*"`.
+///
+/// versions with a pre-release or build metadata can't be serialized this way.
+pub mod seq {
+    use super::*;
+
+    pub fn serialize<S>(version : &Version, serializer : S) -> Result<S::Ok, S::Error> where S : Serializer {
+        if !serializer.is_human_readable() { return compact::serialize(version, serializer); }
+
+        if version.pre().is_some() || version.build().is_some() {
+            return Err(S::Error::custom(format!("{} has labels that can't be put in a sequence", version.to_string())));
+        }
+
+        let parts = version.components();
+        let mut state = serializer.serialize_seq(Some(parts.len()))?;
+        for part in parts {
+            state.serialize_element(part)?;
+        }
+        state.end()
+    }
+
+    pub fn deserialize<'de, D>(deserializer : D) -> Result<Version, D::Error> where D : Deserializer<'de> {
+        super::deserialize(deserializer)
+    }
+}
+
+/// a tuple of the numbers, the wildcard, the pre-release and build, `([1, 2], Some("*"), None, None)`.
+///
+/// this is what is used for formats that aren't human readable, but can also be used
+/// directly to get the same encoding in any format.
+pub mod compact {
+    use super::*;
+
+    pub fn serialize<S>(version : &Version, serializer : S) -> Result<S::Ok, S::Error> where S : Serializer {
+        serialize_parts(version.components(), version.pre(), version.build(), serializer)
+    }
+
+    /// the same tuple from the pieces of a version, so `VersionRef` can use it too
+    pub(crate) fn serialize_parts<S>(parts : &[VersionPart], pre : Option<&str>, build : Option<&str>, serializer : S) -> Result<S::Ok, S::Error> where S : Serializer {
+        let numbers : Vec<u32> = parts.iter().filter_map(|part| match part {
+            VersionPart::Number(number) => Some(*number),
+            VersionPart::Wildcard(_) => None,
+        }).collect();
+        let wildcard : Option<&str> = parts.last().filter(|part| part.is_wildcard()).map(|_| "*");
+
+        let mut state = serializer.serialize_tuple(4)?;
+        state.serialize_element(&numbers)?;
+        state.serialize_element(&wildcard)?;
+        state.serialize_element(&pre)?;
+        state.serialize_element(&build)?;
+        state.end()
+    }
+
+    pub fn deserialize<'de, D>(deserializer : D) -> Result<Version, D::Error> where D : Deserializer<'de> {
+        deserializer.deserialize_tuple(4, CompactVisitor)
+    }
+}
+
+/// deserializes a version from any of the representations, or the compact one if the
+/// format isn't human readable
+pub(crate) fn deserialize<'de, D>(deserializer : D) -> Result<Version, D::Error> where D : Deserializer<'de> {
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_any(AnyVersionVisitor)
+    } else {
+        compact::deserialize(deserializer)
+    }
+}
+
+struct CompactVisitor;
+
+impl <'de> Visitor<'de> for CompactVisitor {
+    type Value = Version;
+
+    fn expecting(&self, formatter : &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a compact version tuple")
+    }
+
+    fn visit_seq<A>(self, mut seq : A) -> Result<Version, A::Error> where A : SeqAccess<'de> {
+        let numbers : Vec<u32> = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let wildcard : Option<char> = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        let pre : Option<String> = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(2, &self))?;
+        let build : Option<String> = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(3, &self))?;
+
+        let mut parts : Parts = numbers.into_iter().map(VersionPart::Number).collect();
+        if let Some(wildcard) = wildcard {
+            parts.push(VersionPart::Wildcard(wildcard));
+        }
+        check_parts(&parts)?;
+        check_labels(pre.as_deref(), build.as_deref(), &self)?;
+
+        Ok(Version::from_parts(parts, pre, build))
+    }
+}
+
+/// deserializes a `VersionRef` from a borrowed string, or the compact tuple with borrowed
+/// labels if the format isn't human readable
+pub(crate) fn deserialize_ref<'de, D>(deserializer : D) -> Result<VersionRef<'de>, D::Error> where D : Deserializer<'de> {
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_str(VersionRefVisitor)
+    } else {
+        deserializer.deserialize_tuple(4, VersionRefVisitor)
+    }
+}
+
+struct VersionRefVisitor;
+
+impl <'de> Visitor<'de> for VersionRefVisitor {
+    type Value = VersionRef<'de>;
+
+    fn expecting(&self, formatter : &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a borrowed version string or compact version tuple")
+    }
+
+    fn visit_borrowed_str<E>(self, string : &'de str) -> Result<VersionRef<'de>, E> where E : de::Error {
+        VersionParser::new().parse_ref(string)
+            .or_else(|_| VersionParser::new().separators(&['_']).parse_ref(string))
+            .map_err(|_| E::invalid_value(de::Unexpected::Str(string), &self))
+    }
+
+    fn visit_seq<A>(self, mut seq : A) -> Result<VersionRef<'de>, A::Error> where A : SeqAccess<'de> {
+        let numbers : Vec<u32> = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let wildcard : Option<char> = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        let pre : Option<&'de str> = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(2, &self))?;
+        let build : Option<&'de str> = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(3, &self))?;
+
+        let mut parts : Parts = numbers.into_iter().map(VersionPart::Number).collect();
+        if let Some(wildcard) = wildcard {
+            parts.push(VersionPart::Wildcard(wildcard));
+        }
+        check_parts(&parts)?;
+        check_labels(pre, build, &self)?;
+
+        Ok(VersionRef::from_parts(parts, pre, build, None))
+    }
+}
+
+/// checks that nothing comes after a wildcard, the same rule the parser uses
+fn check_parts<E>(parts : &[VersionPart]) -> Result<(), E> where E : de::Error {
+    if parts.is_empty() {
+        return Err(E::custom("a version needs at least one component"));
+    }
+    if parts.iter().rev().skip(1).any(VersionPart::is_wildcard) {
+        return Err(E::custom("a version can't have components after a wildcard"));
+    }
+    Ok(())
+}
+
+/// checks the labels are made of valid identifiers, the same rule the parser uses
+fn check_labels<E>(pre : Option<&str>, build : Option<&str>, expected : &dyn de::Expected) -> Result<(), E> where E : de::Error {
+    for label in pre.iter().chain(build.iter()) {
+        if !is_valid_label(label) {
+            return Err(E::invalid_value(de::Unexpected::Str(label), expected));
+        }
+    }
+    Ok(())
+}
+
+struct AnyVersionVisitor;
+
+impl <'de> Visitor<'de> for AnyVersionVisitor {
+    type Value = Version;
+
+    fn expecting(&self, formatter : &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a version string, map or sequence")
+    }
+
+    fn visit_str<E>(self, string : &str) -> Result<Version, E> where E : de::Error {
+        VersionParser::new().parse(string)
+            .or_else(|_| VersionParser::new().separators(&['_']).parse(string))
+            .map_err(|_| E::invalid_value(de::Unexpected::Str(string), &self))
+    }
+
+    fn visit_seq<A>(self, mut seq : A) -> Result<Version, A::Error> where A : SeqAccess<'de> {
+        let mut parts = Parts::new();
+        while let Some(part) = seq.next_element()? {
+            parts.push(part);
+        }
+        check_parts(&parts)?;
+        Ok(Version::from_parts(parts, None, None))
+    }
+
+    fn visit_map<A>(self, mut map : A) -> Result<Version, A::Error> where A : MapAccess<'de> {
+        let mut numbers : [Option<VersionPart>; 3] = [None, None, None];
+        let mut pre : Option<String> = None;
+        let mut build : Option<String> = None;
+
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "major" => numbers[0] = Some(map.next_value()?),
+                "minor" => numbers[1] = Some(map.next_value()?),
+                "patch" => numbers[2] = Some(map.next_value()?),
+                "pre" => pre = Some(map.next_value()?),
+                "build" => build = Some(map.next_value()?),
+                other => return Err(de::Error::unknown_field(other, &FIELDS)),
+            }
+        }
+
+        // the components have to be filled in order, no `patch` without a `minor`
+        let count = numbers.iter().take_while(|part| part.is_some()).count();
+        if numbers[count..].iter().any(Option::is_some) {
+            return Err(de::Error::custom("a version can't skip a component"));
+        }
+        let parts : Parts = numbers.iter_mut().filter_map(Option::take).collect();
+        check_parts(&parts)?;
+
+        check_labels(pre.as_deref(), build.as_deref(), &self)?;
+
+        Ok(Version::from_parts(parts, pre, build))
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////////////////
+// TESTS GO HERE
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Serialize, Deserialize};
+    use serde_test::{Token, Configure, Readable, assert_tokens, assert_de_tokens, assert_de_tokens_error, assert_ser_tokens, assert_ser_tokens_error};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Formats {
+        #[serde(with = "dotted")]
+        dotted : Version,
+        #[serde(with = "underscore")]
+        underscore : Version,
+        #[serde(with = "map")]
+        map : Version,
+        #[serde(with = "seq")]
+        seq : Version,
+    }
+
+    #[test]
+    fn representations() {
+        let formats = Formats {
+            dotted : Version::from_str("1.2.3-rc.1").unwrap(),
+            underscore : Version::from_str("1.2.3").unwrap(),
+            map : Version::from_str("1.2-rc.1+abc").unwrap(),
+            seq : Version::from_str("1.*").unwrap(),
+        };
+
+        assert_tokens(&formats.readable(), &[
+            Token::Struct { name : "Formats", len : 4 },
+            Token::Str("dotted"), Token::Str("1.2.3-rc.1"),
+            Token::Str("underscore"), Token::Str("1_2_3"),
+            Token::Str("map"),
+            Token::Struct { name : "Version", len : 4 },
+            Token::Str("major"), Token::U32(1),
+            Token::Str("minor"), Token::U32(2),
+            Token::Str("pre"), Token::Str("rc.1"),
+            Token::Str("build"), Token::Str("abc"),
+            Token::StructEnd,
+            Token::Str("seq"),
+            Token::Seq { len : Some(2) }, Token::U32(1), Token::Str("*"), Token::SeqEnd,
+            Token::StructEnd,
+        ]);
+    }
+
+    #[test]
+    fn accepts_any_representation() {
+        let version = Version::new(&[1,2,3]).readable();
+
+        assert_de_tokens(&version, &[Token::Str("1.2.3")]);
+        assert_de_tokens(&version, &[Token::Str("1_2_3")]);
+        assert_de_tokens(&version, &[Token::Seq { len : Some(3) }, Token::U8(1), Token::U64(2), Token::I32(3), Token::SeqEnd]);
+        assert_de_tokens(&version, &[
+            Token::Map { len : None },
+            Token::Str("patch"), Token::U32(3),
+            Token::Str("major"), Token::U32(1),
+            Token::Str("minor"), Token::U32(2),
+            Token::MapEnd,
+        ]);
+    }
+
+    #[test]
+    fn rejects_invalid() {
+        assert_de_tokens_error::<Readable<Version>>(&[Token::Seq { len : Some(0) }, Token::SeqEnd],
+            "a version needs at least one component");
+        assert_de_tokens_error::<Readable<Version>>(&[Token::Seq { len : Some(2) }, Token::Str("*"), Token::U32(1), Token::SeqEnd],
+            "a version can't have components after a wildcard");
+        assert_de_tokens_error::<Readable<Version>>(&[Token::Map { len : None }, Token::Str("major"), Token::U32(1), Token::Str("patch"), Token::U32(3), Token::MapEnd],
+            "a version can't skip a component");
+        assert_de_tokens_error::<Readable<Version>>(&[Token::Map { len : None }, Token::Str("epoch")],
+            "unknown field `epoch`, expected one of `major`, `minor`, `patch`, `pre`, `build`");
+        assert_de_tokens_error::<Readable<Version>>(&[Token::Seq { len : Some(1) }, Token::I8(-1)],
+            "invalid value: integer `-1`, expected a version number or wildcard");
+    }
+
+    #[test]
+    fn compact() {
+        let version = Version::from_str("1.2.*").unwrap().compact();
+        assert_tokens(&version, &[
+            Token::Tuple { len : 4 },
+            Token::Seq { len : Some(2) }, Token::U32(1), Token::U32(2), Token::SeqEnd,
+            Token::Some, Token::Str("*"),
+            Token::None,
+            Token::None,
+            Token::TupleEnd,
+        ]);
+
+        // the representation modules are only for human readable formats
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        struct Seq(#[serde(with = "seq")] Version);
+
+        let version = Seq(Version::from_str("1-rc.1").unwrap()).compact();
+        assert_tokens(&version, &[
+            Token::NewtypeStruct { name : "Seq" },
+            Token::Tuple { len : 4 },
+            Token::Seq { len : Some(1) }, Token::U32(1), Token::SeqEnd,
+            Token::None,
+            Token::Some, Token::Str("rc.1"),
+            Token::None,
+            Token::TupleEnd,
+        ]);
+    }
+
+    #[test]
+    fn custom_wildcards() {
+        let parser = VersionParser::new().wildcards(&['x']);
+        let formats = Formats {
+            dotted : parser.parse("1.x").unwrap(),
+            underscore : parser.parse("1.2.x").unwrap(),
+            map : parser.parse("1.x").unwrap(),
+            seq : parser.parse("1.x").unwrap(),
+        };
+        let tokens = [
+            Token::Struct { name : "Formats", len : 4 },
+            Token::Str("dotted"), Token::Str("1.*"),
+            Token::Str("underscore"), Token::Str("1_2_*"),
+            Token::Str("map"),
+            Token::Struct { name : "Version", len : 2 },
+            Token::Str("major"), Token::U32(1),
+            Token::Str("minor"), Token::Str("*"),
+            Token::StructEnd,
+            Token::Str("seq"),
+            Token::Seq { len : Some(2) }, Token::U32(1), Token::Str("*"), Token::SeqEnd,
+            Token::StructEnd,
+        ];
+
+        // written with `*` so the default deserializer reads them back
+        assert_ser_tokens(&formats.readable(), &tokens);
+        let read = Formats {
+            dotted : Version::from_str("1.*").unwrap(),
+            underscore : Version::from_str("1.2.*").unwrap(),
+            map : Version::from_str("1.*").unwrap(),
+            seq : Version::from_str("1.*").unwrap(),
+        };
+        assert_de_tokens(&read.readable(), &tokens);
+
+        assert_tokens(&parser.parse("1.x").unwrap().compact(), &[
+            Token::Tuple { len : 4 },
+            Token::Seq { len : Some(1) }, Token::U32(1), Token::SeqEnd,
+            Token::Some, Token::Str("*"),
+            Token::None,
+            Token::None,
+            Token::TupleEnd,
+        ]);
+        assert_ser_tokens(&parser.parse_ref("1.2.x").unwrap().readable(), &[Token::Str("1.2.*")]);
+    }
+
+    #[test]
+    fn unrepresentable() {
+        #[derive(Serialize)]
+        struct Seq(#[serde(with = "seq")] Version);
+        #[derive(Serialize)]
+        struct Map(#[serde(with = "map")] Version);
+
+        assert_ser_tokens_error(&Seq(Version::from_str("1.2-rc").unwrap()).readable(), &[Token::NewtypeStruct { name : "Seq" }],
+            "1.2-rc has labels that can't be put in a sequence");
+        assert_ser_tokens_error(&Map(Version::new(&[1,2,3,4])).readable(), &[Token::NewtypeStruct { name : "Map" }],
+            "1.2.3.4 has more than 3 components");
+    }
+}
@@ -0,0 +1,254 @@
+//! **sortable** forms of a version, bytes and strings that sort the same way the versions do.
+//!
+//! both sort the way `VersionIndex` does, every component is compared so `1.2` is before
+//! `1.2.0`, and a release is after its pre-releases. the prefix and build metadata are dropped.
+
+use crate::prelude::*;
+use crate::version::Version;
+use crate::identifiers::{is_numeric, parse_number};
+
+/// ends the components and the pre-release label, before anything else so shorter sorts first
+const END : u8 = 0x00;
+/// before a component, plus the number of bytes the number takes (`0` for `0`)
+const NUMBER : u8 = 0x01;
+/// after the components, a pre-release is before the release
+const PRE_RELEASE : u8 = 0x01;
+const RELEASE : u8 = 0x02;
+/// before each identifier in the label, numbers are before text
+const NUMERIC : u8 = 0x01;
+const TEXT : u8 = 0x02;
+
+/// the width the numbers are padded to in the string form, the digits in `u32::MAX`
+const WIDTH : usize = 10;
+
+/// the components as length prefixed big endian numbers, then the pre-release label
+/// identifier by identifier. `None` for patterns with a wildcard
+pub(crate) fn to_bytes(version : &Version) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+
+    for part in version.parts() {
+        push_number(&mut bytes, part.number()?);
+    }
+    bytes.push(END);
+
+    match version.pre() {
+        None => bytes.push(RELEASE),
+        Some(pre) => {
+            bytes.push(PRE_RELEASE);
+            for identifier in pre.split('.') {
+                if is_numeric(identifier) {
+                    // numbers in labels can be any length, so the digits are kept after their count
+                    let digits = identifier.trim_start_matches('0');
+                    bytes.push(NUMERIC);
+                    push_number(&mut bytes, digits.len() as u32);
+                    bytes.extend_from_slice(digits.as_bytes());
+                } else {
+                    // identifiers are alphanumeric, so they can't be confused with the markers
+                    bytes.push(TEXT);
+                    bytes.extend_from_slice(identifier.as_bytes());
+                }
+            }
+            bytes.push(END);
+        },
+    }
+
+    Some(bytes)
+}
+
+/// reads the bytes from `to_bytes`, `None` if they aren't exactly what it would write
+pub(crate) fn from_bytes(mut bytes : &[u8]) -> Option<Version> {
+    let mut numbers = Vec::new();
+    while *bytes.first()? != END {
+        numbers.push(read_number(&mut bytes)?);
+    }
+    take(&mut bytes, 1)?;
+    if numbers.is_empty() { return None; }
+
+    let pre = match take(&mut bytes, 1)? {
+        [RELEASE] => None,
+        [PRE_RELEASE] => Some(read_label(&mut bytes)?),
+        _ => return None,
+    };
+    if !bytes.is_empty() { return None; }
+
+    let version = Version::new(&numbers);
+    match pre {
+        Some(pre) => version.with_pre(&pre).ok(),
+        None => Some(version),
+    }
+}
+
+/// the components padded to 10 digits, ending in a `.` for a release or followed by `-`
+/// and the label for a pre-release, with its numbers padded the same way.
+/// `None` for patterns with a wildcard
+pub(crate) fn to_string(version : &Version) -> Option<String> {
+    let numbers = version.parts()
+        .map(|part| part.number().map(|number| format!("{:01$}", number, WIDTH)))
+        .collect::<Option<Vec<String>>>()?;
+    let mut string = numbers.join(".");
+
+    match version.pre() {
+        // `-` is before `.`, so a pre-release is before its release and both are before
+        // the longer versions
+        None => string.push('.'),
+        Some(pre) => {
+            let identifiers : Vec<String> = pre.split('.')
+                .map(|identifier| match is_numeric(identifier) {
+                    true => format!("{:0>1$}", identifier.trim_start_matches('0'), WIDTH),
+                    false => identifier.to_string(),
+                })
+                .collect();
+            string.push('-');
+            string.push_str(&identifiers.join("."));
+        },
+    }
+
+    Some(string)
+}
+
+/// reads the string from `to_string`
+pub(crate) fn from_string(string : &str) -> Option<Version> {
+    let (numbers, pre) = match string.split_once('-') {
+        Some((numbers, pre)) => (numbers, Some(pre)),
+        None => (string.strip_suffix('.')?, None),
+    };
+    if !numbers.bytes().all(|b| b.is_ascii_digit() || b == b'.') { return None; }
+
+    let numbers = numbers.split('.')
+        .map(|number| if is_numeric(number) { parse_number(number) } else { None })
+        .collect::<Option<Vec<u32>>>()?;
+    let version = Version::new(&numbers);
+
+    match pre {
+        None => Some(version),
+        Some(pre) => {
+            let identifiers : Vec<&str> = pre.split('.')
+                .map(|identifier| match is_numeric(identifier) {
+                    true => match identifier.trim_start_matches('0') {
+                        "" => "0",
+                        digits => digits,
+                    },
+                    false => identifier,
+                })
+                .collect();
+            version.with_pre(&identifiers.join(".")).ok()
+        },
+    }
+}
+
+/// the number of bytes the number needs, then the bytes
+fn push_number(bytes : &mut Vec<u8>, number : u32) {
+    let len = (4 - number.leading_zeros() / 8) as usize;
+    bytes.push(NUMBER + len as u8);
+    bytes.extend_from_slice(&number.to_be_bytes()[4 - len ..]);
+}
+
+fn read_number(bytes : &mut &[u8]) -> Option<u32> {
+    let len = take(bytes, 1)?[0].checked_sub(NUMBER)? as usize;
+    if len > 4 { return None; }
+
+    let number = take(bytes, len)?;
+    // a leading zero byte would be a second way to write the number
+    if number.first() == Some(&0) { return None; }
+    Some(number.iter().fold(0, |total, byte| total << 8 | *byte as u32))
+}
+
+/// the identifiers up to the `END`, joined with `.`
+fn read_label(bytes : &mut &[u8]) -> Option<String> {
+    let mut identifiers = Vec::new();
+
+    loop {
+        match take(bytes, 1)? {
+            [END] => break,
+            [NUMERIC] => {
+                let len = read_number(bytes)? as usize;
+                let digits = core::str::from_utf8(take(bytes, len)?).ok()?;
+                if !digits.is_empty() && (!is_numeric(digits) || digits.starts_with('0')) { return None; }
+                identifiers.push(if digits.is_empty() { "0" } else { digits });
+            },
+            [TEXT] => {
+                let len = bytes.iter().position(|byte| *byte <= TEXT).unwrap_or(bytes.len());
+                let text = core::str::from_utf8(take(bytes, len)?).ok()?;
+                // a number written as text would sort in the wrong place
+                if text.is_empty() || text.contains('.') || is_numeric(text) { return None; }
+                identifiers.push(text);
+            },
+            _ => return None,
+        }
+    }
+
+    Some(identifiers.join("."))
+}
+
+/// splits `len` bytes off the front
+fn take<'a>(bytes : &mut &'a [u8], len : usize) -> Option<&'a [u8]> {
+    if bytes.len() < len { return None; }
+    let (taken, rest) = bytes.split_at(len);
+    *bytes = rest;
+    Some(taken)
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////////////////
+// TESTS GO HERE
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cmp::Ordering;
+    use crate::index::index_cmp;
+
+    const LIST : [&str; 16] = [
+        "1.0.0", "1.2.0-rc.1", "1.2.0", "1.2", "1.2.0.1", "1.2.5", "1.10.0", "2.0.0-beta",
+        "2.0.0-beta.2", "2.0.0-beta.11", "2.0.0-beta.b", "2.0.0-beta.2.1", "0.9", "1", "4294967295.256", "0",
+    ];
+
+    fn sorted<K : Ord>(key : impl Fn(&Version) -> K) -> Vec<String> {
+        let mut versions : Vec<Version> = LIST.iter().filter_map(|v| Version::from_str(v)).collect();
+        versions.sort_by_key(key);
+        versions.iter().map(|v| v.to_string()).collect()
+    }
+
+    fn expected() -> Vec<String> {
+        let mut versions : Vec<Version> = LIST.iter().filter_map(|v| Version::from_str(v)).collect();
+        versions.sort_by(index_cmp);
+        versions.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn bytes() {
+        assert_eq!(sorted(|v| to_bytes(v).unwrap()), expected());
+
+        for version in LIST.iter().filter_map(|v| Version::from_str(v)) {
+            let decoded = from_bytes(&to_bytes(&version).unwrap()).unwrap();
+            assert_eq!(index_cmp(&decoded, &version), Ordering::Equal);
+        }
+
+        assert_eq!(to_bytes(&Version::from_str("1.256-rc.02+abc").unwrap()).unwrap(), vec![
+            0x02, 0x01, 0x03, 0x01, 0x00, 0x00,
+            0x01, 0x02, b'r', b'c', 0x01, 0x02, 0x01, b'2', 0x00,
+        ]);
+        assert_eq!(from_bytes(&[0x02, 0x01, 0x00, 0x01, 0x02, b'x', 0x00]).unwrap().to_string(), "1-x");
+        assert_eq!(to_bytes(&Version::from_str("1.*").unwrap()), None);
+
+        assert_eq!(from_bytes(&[]), None);
+        assert_eq!(from_bytes(&[0x00, 0x02]), None);
+        assert_eq!(from_bytes(&[0x02, 0x01, 0x00, 0x02, 0x02]), None);
+        assert_eq!(from_bytes(&[0x03, 0x00, 0x01, 0x00, 0x02]), None);
+        assert_eq!(from_bytes(&[0x02, 0x01, 0x00, 0x01, 0x02, b'1', 0x00]), None);
+    }
+
+    #[test]
+    fn strings() {
+        assert_eq!(sorted(|v| to_string(v).unwrap()), expected());
+
+        let version = Version::from_str("1.2.0-rc.01").unwrap();
+        assert_eq!(to_string(&version).unwrap(), "0000000001.0000000002.0000000000-rc.0000000001");
+        assert_eq!(to_string(&Version::from_str("3+abc").unwrap()).unwrap(), "0000000003.");
+        assert_eq!(from_string("0000000001.0000000002.0000000000-rc.0000000001").unwrap().to_string(), "1.2.0-rc.1");
+        assert_eq!(from_string("0000000003.").unwrap().to_string(), "3");
+
+        assert_eq!(from_string("0000000003"), None);
+        assert_eq!(from_string("0000000003.*."), None);
+        assert_eq!(from_string("-rc"), None);
+    }
+}
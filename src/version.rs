@@ -1,17 +1,70 @@
 //! the main **version** struct.
 
-use std::fmt;
-use std::cmp::Ordering;
+use core::fmt;
+use core::cmp::Ordering;
+use core::ops::Index;
 
-use serde;
-
-use std::marker::PhantomData;
+use alloc::borrow::Cow;
 
+use crate::prelude::*;
 use crate::versionpart::VersionPart;
+use crate::parts::Parts;
+use crate::parser::VersionParser;
+use crate::format::{self, VersionFormatter};
+use crate::normalize::Normalization;
+use crate::identifiers::{compare_labels, is_valid_label};
+use crate::builder::{VersionBuilder, VersionError, check_part};
+use crate::sortable;
+use crate::index::index_cmp;
+#[cfg(feature = "serde")]
+use crate::serde_format;
+
+/// which pre-release versions the `*_compatible` selectors can pick
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum PreReleasePolicy {
+    /// pre-releases are picked the same as releases
+    Include,
+    /// pre-releases are never picked
+    Exclude,
+    /// pre-releases are only picked if the requirement is a pre-release too, the default
+    #[default]
+    IfRequested,
+}
+
+/// the components a version can be stepped by, see `Version::next`
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Part {
+    Major,
+    Minor,
+    Patch,
+}
 
-#[derive(Hash)]
+impl Part {
+    /// the index of the component
+    pub(crate) const fn index(self) -> usize {
+        match self {
+            Part::Major => 0,
+            Part::Minor => 1,
+            Part::Patch => 2,
+        }
+    }
+}
+
+/// a version or a version pattern, `1.2.3-rc.1` or `1.*`.
+///
+/// it isn't `Hash`, equality only looks at the components both versions have so `1.2 == 1.2.0`
+/// and `1.* == 1.5`, and no hash can agree with that. `canonical().to_string()` or
+/// `to_sortable_bytes` make keys for a `HashMap`, and `VersionIndex` keeps a sorted set.
+#[derive(Clone)]
 pub struct Version {
-    parts : Vec<VersionPart>
+    /// the first 4 parts are stored inline, so most versions only allocate for labels
+    parts : Parts,
+    /// the pre-release label, `rc.1` in `1.2.3-rc.1`. borrowed when the version is a constant
+    pre : Option<Cow<'static, str>>,
+    /// the build metadata, `abc` in `1.2.3+abc`. not used when comparing
+    build : Option<Cow<'static, str>>,
+    /// the `v` or `V` the version was written with, only used when rendering
+    prefix : Option<char>,
 }
 
 impl PartialEq for Version {
@@ -19,45 +72,16 @@ impl PartialEq for Version {
         //! in order for a version to be equal all the parts need to be equal.
         //! and all parts need to be numbers `==` comparisons will always yield 
         //! false when comparing against a pattern.
-        
-        let depth : usize = Version::get_shared_depth(&self, other);
-
-        for i in 0 .. depth {
-            // checks if there is a wildcard, if there is then we assume the previous 
-            // checks were all OK, and we ignore everything after a wildcard.
-            if self.parts[i].is_wildcard() || other.parts[i].is_wildcard() { return true; }
-            
-            // if the two parts don't equal, and neither was a wildcard (above), then
-            // we don't have the same version
-            if self.parts[i] != other.parts[i] { return false}
-        }
-
-        // if we get to this point then they always matched, then we are the same
-        return true;
+        versions_eq(&self.parts, self.pre(), &other.parts, other.pre())
     }
 }
 
 impl Eq for Version { }
 
 
-impl std::cmp::Ord for Version {
+impl Ord for Version {
     fn cmp(&self, other : &Version) -> Ordering {
-        let depth : usize = Version::get_shared_depth(&self, other);
-
-        // checks each parts, drilling down deeper in the version
-        // struct
-        for i in 0 .. depth {
-            // checks if they are equal, if they are equal then 
-            // we won't do anything and check the next part
-            if self.parts[i] != other.parts[i] {
-                // if they are not equal then we compare those parts
-                // we only need to do this once and then return it
-                return self.parts[i].cmp(&other.parts[i]);
-            }
-        }
-        
-        // we should never get here unless the two are the same ..
-        Ordering::Equal
+        versions_cmp(&self.parts, self.pre(), &other.parts, other.pre())
     }
 }
 
@@ -76,63 +100,190 @@ impl fmt::Debug for Version {
 
 impl fmt::Display for Version {
     fn fmt(&self,f:&mut fmt::Formatter) -> fmt::Result {
-        //! prints "x.x.x"
-        write!(f, "Version ({})",self.to_string())
+        //! prints "x.x.x", with the "-pre+build" if it has them.
+        //!
+        //! `{:#}` adds the prefix (`v` if it wasn't parsed with one), the precision sets the
+        //! number of components (`{:.2}` is "x.x") and a zero padded width pads every number
+        //! (`{:03}` is "00x.00x.00x"). use `VersionFormatter` for other separators.
+        format::display(self, f)
     }
 }
 
 
-impl Version {
+/// checks how deep to compare. if they are different lenghts but all the 
+/// numbers of the same length (i.e. `"1.2.3.4" == "1.2.3"`) then it will assume
+/// that the smaller one has a wildcard at the end. 
+fn get_shared_depth(v1 : &[VersionPart], v2 : &[VersionPart]) -> usize {
+    v1.len().min(v2.len())
+}
+
+/// checks if the first numbers are the start of the second
+fn is_prefix(numbers : &[VersionPart], other : &[VersionPart]) -> bool {
+    numbers.len() <= other.len() && numbers == &other[.. numbers.len()]
+}
+
+/// the equality of `Version`, on the parts and pre-release so `VersionRef` can use it too
+pub(crate) fn versions_eq(parts : &[VersionPart], pre : Option<&str>, other_parts : &[VersionPart], other_pre : Option<&str>) -> bool {
+    let depth : usize = get_shared_depth(parts, other_parts);
+
+    for i in 0 .. depth {
+        // checks if there is a wildcard, if there is then we assume the previous 
+        // checks were all OK, and we ignore everything after a wildcard.
+        if parts[i].is_wildcard() || other_parts[i].is_wildcard() { return true; }
+        
+        // if the two parts don't equal, and neither was a wildcard (above), then
+        // we don't have the same version
+        if parts[i] != other_parts[i] { return false}
+    }
+
+    // if we get to this point then they always matched, then we are the same
+    // as long as they are the same pre-release
+    pre == other_pre
+}
+
+/// the ordering of `Version`, on the parts and pre-release so `VersionRef` can use it too
+pub(crate) fn versions_cmp(parts : &[VersionPart], pre : Option<&str>, other_parts : &[VersionPart], other_pre : Option<&str>) -> Ordering {
+    let depth : usize = get_shared_depth(parts, other_parts);
+
+    // checks each parts, drilling down deeper in the version
+    // struct
+    for i in 0 .. depth {
+        // checks if they are equal, if they are equal then 
+        // we won't do anything and check the next part
+        if parts[i] != other_parts[i] {
+            // if they are not equal then we compare those parts
+            // we only need to do this once and then return it
+            return parts[i].cmp(&other_parts[i]);
+        }
+    }
+    
+    // the numbers are the same, so it comes down to the pre-release, 
+    // a release is always greater than any of its pre-releases
+    match (pre, other_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => compare_labels(a, b, false),
+    }
+}
+
+/// the compatibility check of `Version`, on the parts and pre-release so `VersionRef` can use it too
+pub(crate) fn versions_compatible(parts : &[VersionPart], pre : Option<&str>, other_parts : &[VersionPart], other_pre : Option<&str>) -> bool {
+    // if the version number is a wildcard, it can not be compatible with anything else,
+    // compatibility is only for compairing real numbers against other real or wildcard numbers
+    if parts.iter().any(VersionPart::is_wildcard) { return false; }
+    if other_parts.iter().all(VersionPart::is_wildcard) { return true; }
+
+    // same version so it is compatible
+    if versions_eq(parts, pre, other_parts, other_pre) { return true; }
 
-    /// checks how deep to compare. if they are different lenghts but all the 
-    /// numbers of the same length (i.e. `"1.2.3.4" == "1.2.3"`) then it will assume
-    /// that the smaller one has a wildcard at the end. 
-    fn get_shared_depth(v1 : &Version, v2 : &Version) -> usize {
+    let depth : usize = get_shared_depth(parts, other_parts);
 
-        if v1.parts.len() <= v2.parts.len() {
-            return v1.parts.len() 
-        } else { 
-            return v2.parts.len(); 
+    for i in 0 .. depth {
+        if let VersionPart::Number(n) = parts[i] {
+            match other_parts[i] {
+                VersionPart::Number(on) => { if on != n { return false; } },
+                VersionPart::Wildcard(_) => { return true; }
+            }
         }
     }
 
+    false
+}
+
+impl Version {
+
     // initalizers
 
-    /// creates a new version directly from an array of `u8`.
-    pub fn new(numbers : &[u8]) -> Version {
+    /// creates a new version directly from an array of numbers.
+    pub fn new(numbers : &[u32]) -> Version {
         
-        let mut parts : Vec<VersionPart> = Vec::new();
-
-        for i in 0 .. numbers.len() {
-            parts.push(VersionPart::Number(numbers[i]));
-        }
+        let parts : Parts = numbers.iter().map(|number| VersionPart::Number(*number)).collect();
 
-        Version { parts : parts }
+        Version { parts : parts, pre : None, build : None, prefix : None }
     }
 
     /// creates a new wildcard version,`*`, which matches compatible with everything
     pub fn new_wildcard() -> Version {
 
-        Version { parts : vec!(VersionPart::Wildcard("*".to_string())) } 
+        let mut parts = Parts::new();
+        parts.push(VersionPart::Wildcard('*'));
+
+        Version { parts, pre : None, build : None, prefix : None } 
+    }
+
+    /// creates a version from its parts, used by the parser
+    pub(crate) fn from_parts(parts : Parts, pre : Option<String>, build : Option<String>) -> Version {
+        Version { parts, pre : pre.map(Cow::Owned), build : build.map(Cow::Owned), prefix : None }
+    }
+
+    /// creates a version from parts that were checked while compiling, used by `version!`
+    pub(crate) const fn from_static(parts : Parts, pre : Option<&'static str>, build : Option<&'static str>) -> Version {
+        let pre = match pre { Some(pre) => Some(Cow::Borrowed(pre)), None => None };
+        let build = match build { Some(build) => Some(Cow::Borrowed(build)), None => None };
+        Version { parts, pre, build, prefix : None }
+    }
+
+    /// the components of the version
+    pub(crate) fn components(&self) -> &[VersionPart] {
+        &self.parts
+    }
+
+    // components
+
+    /// the number of components, wildcards included. `1.2.*` has 3
+    pub fn len(&self) -> usize {
+        self.parts.len()
+    }
+
+    /// always false, a version has at least one component
+    pub fn is_empty(&self) -> bool {
+        self.parts.is_empty()
+    }
+
+    /// the component at the index, `None` if the version is shorter
+    pub fn get(&self, index : usize) -> Option<&VersionPart> {
+        self.parts.get(index)
+    }
+
+    /// the first component, `1` in `1.2.3`
+    pub fn major(&self) -> Option<&VersionPart> {
+        self.get(0)
+    }
+
+    /// the second component, `2` in `1.2.3`. `None` for `1`, and a wildcard for `1.*`
+    pub fn minor(&self) -> Option<&VersionPart> {
+        self.get(1)
+    }
+
+    /// the third component, `3` in `1.2.3`
+    pub fn patch(&self) -> Option<&VersionPart> {
+        self.get(2)
+    }
+
+    /// iterates over the components
+    pub fn parts(&self) -> core::slice::Iter<'_, VersionPart> {
+        self.parts.iter()
     }
 
     /// creates a version from a string with a custom split string.
     ///
     /// expecting something along the lines of ([0-9]+) ... ($version_string_splitter$)([0-9]+)
+    #[deprecated(note = "use `VersionParser::separators` instead")]
     pub fn from_str_with(version : &str, version_string_splitter : &str) -> Option<Version> {
         
-        let mut parts : Vec<VersionPart> = Vec::new();
+        let mut parts = Parts::new();
 
         for section in version.split(version_string_splitter) {
-            match section.parse::<u8>() {
+            match section.parse::<u32>() {
                 Ok(number) => parts.push(VersionPart::Number(number)),
                 Err(_) => {
                     // not a number so could be a wildcard??
                     if section == "*" {
-                        parts.push(VersionPart::Wildcard(String::from(section)));
+                        parts.push(VersionPart::Wildcard('*'));
                         
                         // we ignore the rest of the string, so we just return this
-                        return Some(Version { parts });
+                        return Some(Version { parts, pre : None, build : None, prefix : None });
                     }
                     else {
                         // this isn't a version string then.
@@ -144,31 +295,38 @@ impl Version {
 
         match parts.len() {
             0 => None,
-            _ => Some(Version { parts })
+            _ => Some(Version { parts, pre : None, build : None, prefix : None })
         }
 
     }
 
-    /// creates a version from a string
+    /// creates a version from a string, `1.2.3`, `1.*`, `1.2.3-rc.1+abc`
+    ///
+    /// use `VersionParser` for more control, or to know why the string wasn't valid.
     pub fn from_str(version : &str) -> Option<Version> {
-        Version::from_str_with(version, ".")
+        VersionParser::new().parse(version).ok()
     }
 
-    /// creates a disconnected copy
-    pub fn clone(&self) -> Version { 
-        Version::from_str(&self.to_string()).unwrap()
+    /// the version of the package that is being built, from the `CARGO_PKG_VERSION`
+    /// variable that cargo sets when running a build script. `None` if it isn't set.
+    /// the build metadata is kept, the same as `pkg_version!`.
+    ///
+    /// use `pkg_version!` to get the version while compiling.
+    #[cfg(feature = "std")]
+    pub fn from_cargo_env() -> Option<Version> {
+        Version::from_str(&std::env::var("CARGO_PKG_VERSION").ok()?)
     }
 
     /// returns the largest version in the list of strings
     /// assumes they all aren't wildcards (doesn't process wildcards, just skips them from the list)
     ///
     /// if a string is passed that isn't a compatible version then it is ignored, no errors are made.
-    pub fn from_latest_vec(list : &Vec<String>) -> Option<Version> {
+    pub fn from_latest_vec<I>(list : I) -> Option<Version> where I : IntoIterator, I::Item : AsRef<str> {
         let mut list_of_versions : Vec<Version> = Vec::new();
         let mut selected = 0;
 
         for l in list { 
-            if let Some(ver) = Version::from_str(l) { 
+            if let Some(ver) = Version::from_str(l.as_ref()) { 
                 if !ver.has_wildcards() { 
                     list_of_versions.push(ver); 
                 }
@@ -184,40 +342,78 @@ impl Version {
         Some(list_of_versions.remove(selected))
     }
 
-    /// checks a list of strings which is the largest version number
+    /// returns the largest version in the list of strings that is compatible with `self`,
+    /// `None` if there aren't any.
     ///
-    /// will parse the strings with `from_str` when comparing, if the string isn't valid
-    /// it will skip it.
-    pub fn latest_compatible<'a>(&self, list : &'a Vec<String>) -> Option<&'a str> {
-        let mut latest = 0;
-        for i in 1..list.len() {
-            if let Some(ver) = Version::from_str(&list[i]){
-                if ver.is_compatible_with(&self) { 
-                    let ver_latest = Version::from_str(&list[latest]).unwrap();
-                    if ver_latest < ver {
-                        latest = i; 
-                    }
-                }
-            }
-        }
+    /// the strings are parsed with `from_str`, if a string isn't valid it is skipped. the
+    /// versions are compared the way `VersionIndex` sorts, so `1.2.1` is after `1.2`.
+    pub fn latest_compatible<'a, I, S>(&self, list : I, policy : PreReleasePolicy) -> Option<&'a str>
+        where I : IntoIterator<Item = &'a S>, S : AsRef<str> + ?Sized + 'a
+    {
+        self.compatible_strs(list, policy).max_by(|a, b| index_cmp(&a.1, &b.1)).map(|(string, _)| string)
+    }
 
-        if list.len() > 0 { Some(&list[latest]) } else { None } 
+    /// returns the smallest version in the list of strings that is compatible with `self`,
+    /// `None` if there aren't any. invalid strings are skipped.
+    pub fn oldest_compatible<'a, I, S>(&self, list : I, policy : PreReleasePolicy) -> Option<&'a str>
+        where I : IntoIterator<Item = &'a S>, S : AsRef<str> + ?Sized + 'a
+    {
+        self.compatible_strs(list, policy).min_by(|a, b| index_cmp(&a.1, &b.1)).map(|(string, _)| string)
     }
 
-    /// checks a list of versions and returns the one that is the largest compatible version
+    /// returns all the strings in the list that are versions compatible with `self`,
+    /// in the order of the list. invalid strings are skipped.
+    pub fn all_compatible<'a, I, S>(&self, list : I, policy : PreReleasePolicy) -> Vec<&'a str>
+        where I : IntoIterator<Item = &'a S>, S : AsRef<str> + ?Sized + 'a
+    {
+        self.compatible_strs(list, policy).map(|(string, _)| string).collect()
+    }
+
+    /// returns the largest version in the list that is compatible with `self`, `None` if
+    /// there aren't any.
     ///
-    /// uses implicit and explicit wildcards for the comparison.
-    pub fn latest_compatible_version<'a>(&self,list : &'a Vec<Version>) -> Option<&'a Version> {
-        let mut latest = 0;
-        for i in 1..list.len() {
-            if list[i].is_compatible_with(&self) { 
-                if &list[latest] < &list[i] {
-                    latest = i; 
-                }
-            }
-        }
+    /// uses implicit and explicit wildcards for the matching, and the `VersionIndex` order
+    /// to pick the largest.
+    pub fn latest_compatible_version<'a, I>(&self, list : I, policy : PreReleasePolicy) -> Option<&'a Version>
+        where I : IntoIterator<Item = &'a Version>
+    {
+        list.into_iter().filter(|version| self.selects(version, policy)).max_by(|a, b| index_cmp(a, b))
+    }
 
-        if list.len() > 0 { Some(&list[latest]) } else { None } 
+    /// returns the smallest version in the list that is compatible with `self`, `None` if
+    /// there aren't any.
+    pub fn oldest_compatible_version<'a, I>(&self, list : I, policy : PreReleasePolicy) -> Option<&'a Version>
+        where I : IntoIterator<Item = &'a Version>
+    {
+        list.into_iter().filter(|version| self.selects(version, policy)).min_by(|a, b| index_cmp(a, b))
+    }
+
+    /// returns all the versions in the list that are compatible with `self`, in the
+    /// order of the list.
+    pub fn all_compatible_versions<'a, I>(&self, list : I, policy : PreReleasePolicy) -> Vec<&'a Version>
+        where I : IntoIterator<Item = &'a Version>
+    {
+        list.into_iter().filter(|version| self.selects(version, policy)).collect()
+    }
+
+    /// parses the strings once, keeping the ones that can be selected
+    fn compatible_strs<'a, 's, I, S>(&'s self, list : I, policy : PreReleasePolicy) -> impl Iterator<Item = (&'a str, Version)> + 's
+        where I : IntoIterator<Item = &'a S> + 's, S : AsRef<str> + ?Sized + 'a
+    {
+        list.into_iter()
+            .filter_map(|string| Version::from_str(string.as_ref()).map(|version| (string.as_ref(), version)))
+            .filter(move |(_, version)| self.selects(version, policy))
+    }
+
+    /// checks if the version can be selected by `self` as a requirement
+    pub(crate) fn selects(&self, version : &Version, policy : PreReleasePolicy) -> bool {
+        if !version.is_compatible_with(self) { return false; }
+
+        match policy {
+            PreReleasePolicy::Include => true,
+            PreReleasePolicy::Exclude => !version.is_prerelease(),
+            PreReleasePolicy::IfRequested => !version.is_prerelease() || self.is_prerelease(),
+        }
     }
 
     // checking functions, to get general booleans
@@ -257,100 +453,333 @@ impl Version {
     /// uses wildcards in the comparision. if the `self` version has wildcards then it will not be 
     /// compatible with anything else since it is not an actual version
     pub fn is_compatible_with(&self,other : &Version) -> bool {
-        // if the version number is a wildcard, it can not be compatible with anything else,
-        // compatibility is only for compairing real numbers against other real or wildcard numbers
-        if self.has_wildcards() { return false; }
-        if other.is_wildcard() { return true; }
+        versions_compatible(&self.parts, self.pre(), &other.parts, other.pre())
+    }
 
-        // same version so it is compatible
-        if self == other { return true; }
+    // patterns
 
-        let depth : usize = Version::get_shared_depth(&self, other);
+    /// checks if every version that matches `other` also matches this pattern, `1.*` subsumes
+    /// `1.2.*` and `1.2.3`.
+    ///
+    /// the missing components of a shorter version are a wildcard, so `1.2` subsumes `1.2.3`.
+    /// a pattern without a wildcard only matches its own pre-release label, but a wildcard
+    /// matches any of them.
+    pub fn subsumes(&self, other : &Version) -> bool {
+        let (numbers, wildcard) = self.pattern();
+        let (other_numbers, other_wildcard) = other.pattern();
+
+        is_prefix(numbers, other_numbers) && (wildcard || (!other_wildcard && self.pre() == other.pre()))
+    }
 
-        for i in 0 .. depth {
-            if let VersionPart::Number(n) = self.parts[i] {
-                match other.parts[i] {
-                    VersionPart::Number(on) => { if on != n { return false; } },
-                    VersionPart::Wildcard(_) => { return true; }
-                }
-            }
+    /// checks if there is a version that matches both patterns, `1.*` and `1.2.*` overlap
+    /// but `1.2.*` and `1.3` don't
+    pub fn overlaps(&self, other : &Version) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// the pattern that matches the versions that match both patterns, `1.2.*` for `1.*`
+    /// and `1.2.*`. `None` if they don't overlap
+    pub fn intersection(&self, other : &Version) -> Option<Version> {
+        let (numbers, wildcard) = self.pattern();
+        let (other_numbers, other_wildcard) = other.pattern();
+
+        if !is_prefix(numbers, other_numbers) && !is_prefix(other_numbers, numbers) { return None; }
+        // only a wildcard matches other pre-release labels
+        if !wildcard && !other_wildcard && self.pre() != other.pre() { return None; }
+
+        let longer = if numbers.len() >= other_numbers.len() { self } else { other };
+        let mut parts : Parts = longer.pattern().0.iter().copied().collect();
+        // a pattern without a wildcard fixes the pre-release label
+        let pre = match (wildcard, other_wildcard) {
+            (true, true) => {
+                parts.push(*longer.parts.last()?);
+                None
+            },
+            (true, false) => other.pre.clone(),
+            (false, _) => self.pre.clone(),
+        };
+
+        Some(Version { parts, pre, build : None, prefix : None })
+    }
+
+    /// the numbers before the wildcard, and if there is one
+    fn pattern(&self) -> (&[VersionPart], bool) {
+        match self.parts.split_last() {
+            Some((last, numbers)) if last.is_wildcard() => (numbers, true),
+            _ => (&self.parts, false),
         }
+    }
 
-        false
+    // stepping
+
+    /// the next major version, `1.2.3` to `2.0.0`, see `Version::next`
+    pub fn next_major(&self) -> Option<Version> {
+        self.next(Part::Major)
+    }
+
+    /// the next minor version, `1.2.3` to `1.3.0`, see `Version::next`
+    pub fn next_minor(&self) -> Option<Version> {
+        self.next(Part::Minor)
+    }
+
+    /// the next patch version, `1.2.3` to `1.2.4`, see `Version::next`
+    pub fn next_patch(&self) -> Option<Version> {
+        self.next(Part::Patch)
+    }
+
+    /// increases the part and sets the components after it to `0`, missing components are
+    /// added so `1.2` is `1.2.1` for the patch. the labels are dropped, and a pre-release
+    /// with only `0`s after the part steps to its release, `1.3.0-rc.1` to `1.3.0` for the minor.
+    ///
+    /// `None` if the version has a wildcard or the part would overflow
+    pub fn next(&self, part : Part) -> Option<Version> {
+        if self.has_wildcards() { return None; }
+
+        let index = part.index();
+        let mut numbers : Vec<u32> = self.parts.iter().filter_map(VersionPart::number).collect();
+        if numbers.len() <= index { numbers.resize(index + 1, 0); }
+
+        let (stepped, after) = numbers.split_at_mut(index + 1);
+        if !self.is_prerelease() || after.iter().any(|number| *number != 0) {
+            stepped[index] = stepped[index].checked_add(1)?;
+            after.iter_mut().for_each(|number| *number = 0);
+        }
+
+        let mut next = Version::new(&numbers);
+        next.prefix = self.prefix;
+        Some(next)
+    }
+
+    /// the smallest version above this one, `1.2.3` to `1.2.4-0`. longer versions like
+    /// `1.2.3.1` are equal to `1.2.3`, so only pre-releases of `1.2.4` come between them.
+    /// a pre-release gets a `0` added to its label, `1.2.3-rc.1` to `1.2.3-rc.1.0`.
+    ///
+    /// `None` if the version has a wildcard or the last component would overflow
+    pub fn successor(&self) -> Option<Version> {
+        if self.has_wildcards() { return None; }
+
+        let mut parts = self.parts.clone();
+        let pre = match self.pre() {
+            Some(pre) => format!("{}.0", pre),
+            None => {
+                let last = parts.len() - 1;
+                parts[last] = VersionPart::Number(parts[last].number()?.checked_add(1)?);
+                "0".to_string()
+            },
+        };
+
+        Some(Version { parts, pre : Some(Cow::Owned(pre)), build : None, prefix : self.prefix })
+    }
+
+    /// the smallest version above this one when every component is compared, the way
+    /// `VersionIndex` sorts, for versions without pre-releases: `1.2.3` to `1.2.3.0`.
+    /// a pre-release gets a `0` added to its label the same as `Version::successor`.
+    ///
+    /// `None` if the version has a wildcard
+    pub fn dotted_successor(&self) -> Option<Version> {
+        if self.has_wildcards() { return None; }
+        if self.is_prerelease() { return self.successor(); }
+
+        let mut parts = self.parts.clone();
+        parts.push(VersionPart::Number(0));
+        Some(Version { parts, pre : None, build : None, prefix : self.prefix })
+    }
+
+    // normalizing
+
+    /// the canonical form of the version. equal versions of the same length, or that only
+    /// differ in trailing zeros, have the same canonical form, but `1.2` and `1.2.3` are
+    /// equal and don't.
+    ///
+    /// trailing zeros are trimmed and it is padded to 3 components, the prefix and build
+    /// metadata are dropped, `1.2`, `1.2.0.0` and `v1.2.0+abc` are all `1.2.0`.
+    pub fn canonical(&self) -> Version {
+        Normalization::default().normalize(self)
+    }
+
+    /// a copy of the version changed with the rules, see `Normalization`
+    pub fn normalize(&self, rules : &Normalization) -> Version {
+        rules.normalize(self)
+    }
+
+    /// keeps the first `len` components, always at least one
+    pub fn truncate(&mut self, len : usize) {
+        self.parts.truncate(len.max(1));
+    }
+
+    /// adds `0` components until there are at least `len`, nothing is added after a wildcard
+    pub fn pad_to(&mut self, len : usize) {
+        if self.has_wildcards() { return; }
+        while self.parts.len() < len {
+            self.parts.push(VersionPart::Number(0));
+        }
+    }
+
+    /// sets the build metadata, used when normalizing
+    pub(crate) fn set_build(&mut self, build : Option<String>) {
+        self.build = build.map(Cow::Owned);
+    }
+
+    // changing
+
+    /// a builder for making a version from its components, see `VersionBuilder`
+    pub fn builder() -> VersionBuilder {
+        VersionBuilder::new()
+    }
+
+    /// replaces the component at the index, or adds it if the index is the length.
+    ///
+    /// a wildcard can only be the last component, so a wildcard can't be set before other
+    /// components and nothing can be added after one.
+    pub fn set_part(&mut self, index : usize, part : VersionPart) -> Result<(), VersionError> {
+        if let Some(error) = check_part(&self.parts, index, part) { return Err(error); }
+
+        if index < self.parts.len() {
+            self.parts[index] = part;
+        } else {
+            self.parts.push(part);
+        }
+        Ok(())
+    }
+
+    /// adds a component to the end, an error if the version ends in a wildcard
+    pub fn push_part(&mut self, part : VersionPart) -> Result<(), VersionError> {
+        let index = self.parts.len();
+        self.set_part(index, part)
+    }
+
+    /// the same version with the pre-release label, without the leading `-`
+    pub fn with_pre(mut self, pre : &str) -> Result<Version, VersionError> {
+        if !is_valid_label(pre) { return Err(VersionError::InvalidPreRelease(pre.to_string())); }
+        self.pre = Some(Cow::Owned(pre.to_string()));
+        Ok(self)
+    }
+
+    /// the same version without a pre-release label
+    pub fn without_pre(mut self) -> Version {
+        self.pre = None;
+        self
+    }
+
+    /// the same version with the build metadata, without the leading `+`
+    pub fn with_build(mut self, build : &str) -> Result<Version, VersionError> {
+        if !is_valid_label(build) { return Err(VersionError::InvalidBuild(build.to_string())); }
+        self.build = Some(Cow::Owned(build.to_string()));
+        Ok(self)
+    }
+
+    /// the same version without build metadata
+    pub fn without_build(mut self) -> Version {
+        self.build = None;
+        self
     }
 
     // data structure covnersion
 
     
-    /// returns a string formated as "x.x.x.x"
+    /// returns a string formated as "x.x.x.x", with the "-pre+build" if it has them
     pub fn to_string(&self) -> String {
-        
-        let mut rendered_string : String = String::new();
+        VersionFormatter::new().format(self)
+    }
 
-        for i in 0 .. self.parts.len() - 1 {
-            rendered_string += &format!("{}.",self.parts[i]); 
-        }
-        rendered_string += &format!("{}", self.parts[self.parts.len()-1]);
+    /// bytes that sort the same as the versions, for keys in a database. every component is
+    /// compared the way `VersionIndex` sorts, so `1.2` is before `1.2.0`, and the prefix and
+    /// build metadata are dropped. `None` for patterns with a wildcard
+    pub fn to_sortable_bytes(&self) -> Option<Vec<u8>> {
+        sortable::to_bytes(self)
+    }
 
-        return rendered_string;
+    /// reads the bytes from `to_sortable_bytes`, `None` if they aren't a version
+    pub fn from_sortable_bytes(bytes : &[u8]) -> Option<Version> {
+        sortable::from_bytes(bytes)
     }
 
-    /// returns a string formated as "x_x_x_x"
-    pub fn to_string_serializer(&self) -> String {
-        
-        let mut rendered_string : String = String::new();
+    /// a string that sorts the same as the versions, for file and object names, with the numbers
+    /// padded to 10 digits: `0000000001.0000000002.` for `1.2`. labels sort right as long as
+    /// their numbers fit in 10 digits and their text doesn't have a `-`.
+    /// `None` for patterns with a wildcard
+    pub fn to_sortable_string(&self) -> Option<String> {
+        sortable::to_string(self)
+    }
 
-        for i in 0 .. self.parts.len() - 1 {
-            rendered_string += &format!("{}_",self.parts[i]); 
-        }
-        rendered_string += &format!("{}", self.parts[self.parts.len()-1]);
+    /// reads the string from `to_sortable_string`, `None` if it isn't a version
+    pub fn from_sortable_string(string : &str) -> Option<Version> {
+        sortable::from_string(string)
+    }
 
-        return rendered_string;
+    /// the pre-release label, without the leading `-`
+    pub fn pre(&self) -> Option<&str> {
+        self.pre.as_deref()
     }
 
-}
+    /// the build metadata, without the leading `+`
+    pub fn build(&self) -> Option<&str> {
+        self.build.as_deref()
+    }
 
-impl serde::Serialize for Version {
-    fn serialize<S>(&self,serializer : S) -> Result<S::Ok, S::Error> where S : serde::Serializer {
-        serializer.serialize_str(&self.to_string_serializer())
+    /// checks if the version is a pre-release, `1.2.3-rc.1`
+    pub fn is_prerelease(&self) -> bool {
+        self.pre.is_some()
     }
-}
 
-impl <'de> serde::Deserialize<'de> for Version {
-    fn deserialize<D>(deserializer : D) -> Result<Version, D::Error> where D : serde::Deserializer<'de> {
-        deserializer.deserialize_str(VersionVisitor::new())
+    /// the prefix the version was parsed with, `v` or `V`
+    pub fn prefix(&self) -> Option<char> {
+        self.prefix
     }
-}
 
-struct VersionVisitor {
-    marker: PhantomData<fn() -> Version>
-}
+    /// sets the prefix that is used by `to_prefixed_string`, only `v` and `V` are
+    /// valid prefixes, anything else clears it.
+    pub fn set_prefix(&mut self, prefix : Option<char>) {
+        self.prefix = prefix.filter(|c| *c == 'v' || *c == 'V');
+    }
 
-impl VersionVisitor {
-    fn new() -> Self {
-        VersionVisitor {
-            marker : PhantomData
+    /// returns a string formated as "x.x.x.x" with the prefix it was parsed with,
+    /// i.e. "v1.2.3"
+    pub fn to_prefixed_string(&self) -> String {
+        match self.prefix {
+            Some(prefix) => format!("{}{}", prefix, self.to_string()),
+            None => self.to_string(),
         }
     }
+
+    /// returns a string formated as "x_x_x_x", with the "-pre+build" if it has them
+    pub fn to_string_serializer(&self) -> String {
+        VersionFormatter::new().separator("_").format(self)
+    }
+
 }
 
-impl <'de>serde::de::Visitor<'de> for VersionVisitor {
-    type Value = Version;
+impl <'a> IntoIterator for &'a Version {
+    type Item = &'a VersionPart;
+    type IntoIter = core::slice::Iter<'a, VersionPart>;
 
-    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        formatter.write_str("version")
+    fn into_iter(self) -> core::slice::Iter<'a, VersionPart> {
+        self.parts()
     }
+}
 
-    fn visit_str<A>(self, string:&str) -> Result<Self::Value, A> 
-    where A : serde::de::Error,
-    {
-        use serde::de::{Error, Unexpected};
+impl Index<usize> for Version {
+    type Output = VersionPart;
 
-        if let Some(version) = Version::from_str_with(string, "_") { 
-            Ok(version) 
-        } else { 
-            Err(Error::invalid_value(Unexpected::Str(string), &self)) 
-        }
+    fn index(&self, index : usize) -> &VersionPart {
+        //! panics if the version doesn't have that many components, use `get` to check
+        &self.parts[index]
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Version {
+    fn serialize<S>(&self,serializer : S) -> Result<S::Ok, S::Error> where S : serde::Serializer {
+        //! serializes as "x.x.x", see `serde_format` for the other representations
+        serde_format::dotted::serialize(self, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl <'de> serde::Deserialize<'de> for Version {
+    fn deserialize<D>(deserializer : D) -> Result<Version, D::Error> where D : serde::Deserializer<'de> {
+        //! accepts any of the representations in `serde_format`
+        serde_format::deserialize(deserializer)
     }
 }
 
@@ -413,6 +842,41 @@ mod tests {
         assert_eq!(false,super::Version::from_str("21.*").unwrap().is_compatible_with(&super::Version::from_str("22.12").unwrap()));
     }
 
+    #[test]
+    fn patterns() {
+        let v = |s : &str| super::Version::from_str(s).unwrap();
+
+        assert!(v("1.*").subsumes(&v("1.2.*")));
+        assert!(v("1.*").subsumes(&v("1.2.3-rc.1")));
+        assert!(v("*").subsumes(&v("4.*")));
+        assert!(v("1.2").subsumes(&v("1.2.3")));
+        assert!(v("1.2.*").subsumes(&v("1.2.*")));
+        assert!(!v("1.2.*").subsumes(&v("1.*")));
+        assert!(!v("1.2").subsumes(&v("1.2.*")));
+        assert!(!v("1.2").subsumes(&v("1.2.3-rc.1")));
+        assert!(!v("1.1.*").subsumes(&v("1.2")));
+
+        assert!(v("1.*").overlaps(&v("1.2.*")));
+        assert!(v("1.2.*").overlaps(&v("1.*")));
+        assert!(v("1.2").overlaps(&v("1.2.3")));
+        assert!(!v("1.2.*").overlaps(&v("1.3")));
+        assert!(!v("1.2-rc.1").overlaps(&v("1.2-rc.2")));
+
+        let intersection = |a : &str, b : &str| v(a).intersection(&v(b)).map(|version| version.to_string());
+        assert_eq!(intersection("1.*", "1.2.*"), Some("1.2.*".to_string()));
+        assert_eq!(intersection("*", "*"), Some("*".to_string()));
+        assert_eq!(intersection("*", "3.1"), Some("3.1".to_string()));
+        assert_eq!(intersection("1.2.*", "1-rc.1"), Some("1.2-rc.1".to_string()));
+        assert_eq!(intersection("1.*", "1.2.3+abc"), Some("1.2.3".to_string()));
+        assert_eq!(intersection("1.2.*", "1.3"), None);
+
+        // the intersection is subsumed by both
+        for (a, b) in [("1.*", "1.2.*"), ("1.2.*", "1-rc.1"), ("*", "3.1"), ("2.0", "2")] {
+            let intersection = v(a).intersection(&v(b)).unwrap();
+            assert!(v(a).subsumes(&intersection) && v(b).subsumes(&intersection), "{} {}", a, b);
+        }
+    }
+
     #[test]
     fn version_comparisons() {
         assert!(super::Version::from_str("1.1.0").unwrap() > super::Version::from_str("1.0.0").unwrap());
@@ -431,8 +895,21 @@ mod tests {
         ];
 
         let version = Version::from_str("1.*.*").unwrap();
-
-        assert_eq!(version.latest_compatible(&versions).unwrap().to_string(),"1.1.0".to_string());
+        let policy = PreReleasePolicy::default();
+
+        assert_eq!(version.latest_compatible(&versions, policy).unwrap().to_string(),"1.1.0".to_string());
+        assert_eq!(version.oldest_compatible(&versions, policy), Some("1.0.0"));
+        assert_eq!(version.all_compatible(&versions, policy), vec!["1.0.1", "1.0.2", "1.1.0", "1.0.0"]);
+        assert_eq!(version.latest_compatible(&["x.y", "1.0.0"], policy), Some("1.0.0"));
+        assert_eq!(Version::new(&[3]).latest_compatible(&versions, policy), None);
+        assert_eq!(version.latest_compatible(Vec::<&str>::new().iter(), policy), None);
+
+        // versions of different lengths are told apart, whatever order they are in
+        let pattern = Version::from_str("1.*").unwrap();
+        for list in [["1.2.1", "1.2"], ["1.2", "1.2.1"]] {
+            assert_eq!(pattern.latest_compatible(&list, policy), Some("1.2.1"));
+            assert_eq!(pattern.oldest_compatible(&list, policy), Some("1.2"));
+        }
     }
 
     #[test]
@@ -446,14 +923,81 @@ mod tests {
         ];
 
         let version = Version::from_str("1.*.*").unwrap();
-        assert_eq!(version.latest_compatible_version(&versions).unwrap().to_string(),"1.1.0".to_string());
-        assert_eq!(Version::new(&[1]).latest_compatible_version(&versions).unwrap().to_string(),"1.1.0".to_string());
+        let policy = PreReleasePolicy::default();
+        assert_eq!(version.latest_compatible_version(&versions, policy).unwrap().to_string(),"1.1.0".to_string());
+        assert_eq!(Version::new(&[1]).latest_compatible_version(&versions, policy).unwrap().to_string(),"1.1.0".to_string());
+        assert_eq!(version.oldest_compatible_version(&versions, policy).unwrap().to_string(),"1.0.0".to_string());
+        assert_eq!(version.all_compatible_versions(&versions, policy).len(), 4);
+
+        // the first version isn't picked unless it is compatible
+        assert!(Version::new(&[3]).latest_compatible_version(&versions, policy).is_none());
+        assert!(Version::new(&[3]).oldest_compatible_version(&versions, policy).is_none());
+
+        let pattern = Version::from_str("1.*").unwrap();
+        for list in [[Version::new(&[1,2,1]), Version::new(&[1,2])], [Version::new(&[1,2]), Version::new(&[1,2,1])]] {
+            assert_eq!(pattern.latest_compatible_version(&list, policy).unwrap().to_string(), "1.2.1");
+            assert_eq!(pattern.oldest_compatible_version(&list, policy).unwrap().to_string(), "1.2");
+        }
+    }
+
+    #[test]
+    fn pre_release_policy() {
+        let versions : Vec<Version> = ["1.0.0", "1.1.0-rc.1", "1.1.0-rc.2", "0.9.0-beta"].iter()
+            .filter_map(|v| Version::from_str(v))
+            .collect();
+        let latest = |requirement : &str, policy| Version::from_str(requirement).unwrap()
+            .latest_compatible_version(&versions, policy)
+            .map(Version::to_string);
+
+        assert_eq!(latest("1.*", PreReleasePolicy::Include), Some("1.1.0-rc.2".to_string()));
+        assert_eq!(latest("1.*", PreReleasePolicy::Exclude), Some("1.0.0".to_string()));
+        assert_eq!(latest("1.*", PreReleasePolicy::IfRequested), Some("1.0.0".to_string()));
+        assert_eq!(latest("1.1.0-rc.1", PreReleasePolicy::IfRequested), Some("1.1.0-rc.1".to_string()));
+        assert_eq!(latest("0.*", PreReleasePolicy::IfRequested), None);
+    }
+
+    #[test]
+    fn components() {
+        let version = Version::from_str("1.2.*").unwrap();
+
+        assert_eq!(version.len(), 3);
+        assert_eq!(version.major().and_then(VersionPart::number), Some(1));
+        assert_eq!(version.minor(), Some(&VersionPart::Number(2)));
+        assert!(version.patch().unwrap().is_wildcard());
+        assert_eq!(version[2].wildcard(), Some('*'));
+        assert!(version.get(3).is_none());
+        assert_eq!(Version::new(&[4]).minor(), None);
+
+        let numbers : Vec<u32> = version.parts().filter_map(VersionPart::number).collect();
+        assert_eq!(numbers, vec![1, 2]);
+        assert_eq!((&version).into_iter().count(), 3);
+    }
+
+    #[test]
+    fn mutators() {
+        let mut version = Version::new(&[1,2,3]);
+
+        version.set_part(1, VersionPart::Number(7)).unwrap();
+        version.push_part(VersionPart::Number(9)).unwrap();
+        assert_eq!(version.to_string(), "1.7.3.9");
+
+        assert_eq!(version.set_part(1, VersionPart::Wildcard('*')), Err(VersionError::AfterWildcard { index : 2 }));
+        assert_eq!(version.set_part(6, VersionPart::Number(1)), Err(VersionError::MissingComponent { index : 6 }));
+        version.set_part(3, VersionPart::Wildcard('*')).unwrap();
+        assert_eq!(version.push_part(VersionPart::Number(1)), Err(VersionError::AfterWildcard { index : 4 }));
+        assert_eq!(version.to_string(), "1.7.3.*");
+
+        let version = Version::new(&[2,0]).with_pre("rc.1").unwrap().with_build("abc").unwrap();
+        assert_eq!(version.to_string(), "2.0-rc.1+abc");
+        assert_eq!(version.clone().without_build().to_string(), "2.0-rc.1");
+        assert_eq!(version.without_pre().to_string(), "2.0+abc");
+        assert!(Version::new(&[1]).with_pre("a b").is_err());
     }
 
     #[test]
     fn versionpart_is_number() {
         let vp = super::VersionPart::Number(12);
-        let vp2 = super::VersionPart::Wildcard("*".to_string());
+        let vp2 = super::VersionPart::Wildcard('*');
         assert!(vp.is_number());
         assert!(!vp2.is_number());
     }
@@ -461,7 +1005,7 @@ mod tests {
     #[test]
     fn versionpart_is_wildcard() {
         let vp = super::VersionPart::Number(12);
-        let vp2 = super::VersionPart::Wildcard("*".to_string());
+        let vp2 = super::VersionPart::Wildcard('*');
         assert!(!vp.is_wildcard());
         assert!(vp2.is_wildcard());
     }
@@ -470,13 +1014,30 @@ mod tests {
     fn versionpart_equals() {
         let vp = super::VersionPart::Number(14);
         let vp2 = super::VersionPart::Number(65);
-        let vp3 = super::VersionPart::Wildcard("*".to_string());
+        let vp3 = super::VersionPart::Wildcard('*');
         assert!(vp == super::VersionPart::Number(14));
         assert!(vp2 == super::VersionPart::Number(65));
         assert!(vp2 != vp);
         assert!(vp != vp3);
     }
 
+    #[test]
+    #[cfg(feature = "serde")]
+    fn versionpart_serde() {
+        use serde_test::{Token, Configure, assert_tokens, assert_de_tokens, assert_ser_tokens};
+
+        // wildcards are never equal, so they can only be checked one way
+        assert_tokens(&super::VersionPart::Number(12).readable(), &[Token::U32(12)]);
+        assert_ser_tokens(&super::VersionPart::Wildcard('*').readable(), &[Token::Str("*")]);
+        assert_de_tokens(&super::VersionPart::Number(12).readable(), &[Token::Str("12")]);
+        assert_tokens(&super::VersionPart::Number(12).compact(), &[
+            Token::NewtypeVariant { name : "VersionPart", variant : "Number" }, Token::U32(12)
+        ]);
+        assert_ser_tokens(&super::VersionPart::Wildcard('*').compact(), &[
+            Token::NewtypeVariant { name : "VersionPart", variant : "Wildcard" }, Token::Str("*")
+        ]);
+    }
+
     #[test]
     fn version_from_string() {
         let ver1 : super::Version = super::Version::from_str("1.0.0").unwrap();
@@ -494,15 +1055,17 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "serde")]
     fn serde() {
-        use serde_test::{Token, assert_tokens};
+        use serde_test::{Token, Configure, assert_tokens};
 
         let version = super::Version::from_str("0.1.2").unwrap();
-        assert_tokens(&version,&[Token::Str("0_1_2")]);
+        assert_tokens(&version.readable(),&[Token::Str("0.1.2")]);
 
     }
 
     #[test]
+    #[allow(deprecated)]
     fn version_parse_serde() {
         let version = super::Version::from_str_with("0_1_2", "_").unwrap();
         assert_eq!(version, super::Version::new(&[0,1,2]));
@@ -521,5 +1084,35 @@ mod tests {
         assert_eq!(super::Version::from_str("1").unwrap(), super::Version::new(&[1]));
     }
 
+    #[test]
+    fn stepping() {
+        let next = |v : &str, part| Version::from_str(v).unwrap().next(part).map(|v| v.to_string());
+
+        assert_eq!(next("1.2.3", Part::Patch), Some("1.2.4".to_string()));
+        assert_eq!(next("1.2.3.7", Part::Minor), Some("1.3.0.0".to_string()));
+        assert_eq!(next("1.2+abc", Part::Patch), Some("1.2.1".to_string()));
+        assert_eq!(next("1", Part::Major), Some("2".to_string()));
+        assert_eq!(next("1.3.0-rc.1", Part::Minor), Some("1.3.0".to_string()));
+        assert_eq!(next("1.3.1-rc.1", Part::Minor), Some("1.4.0".to_string()));
+        assert_eq!(next("1.2.*", Part::Patch), None);
+        assert_eq!(next("1.4294967295", Part::Minor), None);
+        let prefixed = VersionParser::new().allow_prefix(true).parse("v1.2.3").unwrap();
+        assert_eq!(prefixed.next_minor().unwrap().to_prefixed_string(), "v1.3.0");
+
+        let successor = |v : &str| Version::from_str(v).unwrap().successor().map(|v| v.to_string());
+        assert_eq!(successor("1.2.3"), Some("1.2.4-0".to_string()));
+        assert_eq!(successor("1.2.3-rc.1+abc"), Some("1.2.3-rc.1.0".to_string()));
+        assert_eq!(successor("1.*"), None);
+        assert_eq!(successor("4294967295"), None);
+        assert_eq!(Version::from_str("1.2.3").unwrap().dotted_successor().unwrap().to_string(), "1.2.3.0");
+
+        // nothing is between a version and its successor
+        let version = Version::from_str("1.2.3").unwrap();
+        let successor = version.successor().unwrap();
+        assert!(version < successor);
+        assert!(Version::from_str("1.2.3.9").unwrap() < successor);
+        assert!(Version::from_str("1.2.4-0").unwrap() <= successor);
+    }
+
 
 }
\ No newline at end of file
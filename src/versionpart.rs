@@ -1,17 +1,31 @@
 //! the major, minor, patch sections of the version
 
-use std::fmt;
-use std::cmp::Ordering;
+use core::fmt;
+use core::cmp::Ordering;
 
-use serde::{ Deserialize, Serialize };
-
-#[derive(Hash,Serialize, Deserialize)]
+/// one component of a version, a number or a wildcard
+#[derive(Hash,Debug,Clone,Copy)]
 pub enum VersionPart {
-  Number(u8),
-  Wildcard(String)
+  Number(u32),
+  Wildcard(char)
 }
 
 impl VersionPart { 
+  /// the number, `None` for a wildcard
+  pub fn number(&self) -> Option<u32> {
+    match self {
+      VersionPart::Number(number) => Some(*number),
+      VersionPart::Wildcard(_) => None,
+    }
+  }
+  /// the wildcard character, `None` for a number
+  pub fn wildcard(&self) -> Option<char> {
+    match self {
+      VersionPart::Number(_) => None,
+      VersionPart::Wildcard(wildcard) => Some(*wildcard),
+    }
+  }
+
   pub fn is_number(&self) -> bool {
     match self {
       &VersionPart::Number(_) => { return true; }
@@ -70,7 +84,119 @@ impl fmt::Display for VersionPart {
   fn fmt(&self,f:&mut fmt::Formatter) -> fmt::Result {
     match self {
       &VersionPart::Number(ref num) => { write!(f,"{}",num) }
-      &VersionPart::Wildcard(ref string) => { write!(f,"{}",string) }
+      &VersionPart::Wildcard(ref wildcard) => { write!(f,"{}",wildcard) }
+    }
+  }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for VersionPart {
+  fn serialize<S>(&self, serializer : S) -> Result<S::Ok, S::Error> where S : serde::Serializer {
+    //! the same as it is written in a version, a number or `"*"` whatever wildcard it was
+    //! parsed with. formats that aren't human readable use an enum so the wildcard can be told apart.
+    match (self, serializer.is_human_readable()) {
+      (VersionPart::Number(num), true) => serializer.serialize_u32(*num),
+      (VersionPart::Wildcard(_), true) => serializer.serialize_str("*"),
+      (VersionPart::Number(num), false) => serializer.serialize_newtype_variant("VersionPart", 0, "Number", num),
+      (VersionPart::Wildcard(_), false) => serializer.serialize_newtype_variant("VersionPart", 1, "Wildcard", "*"),
+    }
+  }
+}
+
+#[cfg(feature = "serde")]
+impl <'de> serde::Deserialize<'de> for VersionPart {
+  fn deserialize<D>(deserializer : D) -> Result<VersionPart, D::Error> where D : serde::Deserializer<'de> {
+    if deserializer.is_human_readable() {
+      deserializer.deserialize_any(VersionPartVisitor)
+    } else {
+      deserializer.deserialize_enum("VersionPart", &["Number", "Wildcard"], VersionPartVisitor)
+    }
+  }
+}
+
+#[cfg(feature = "serde")]
+struct VersionPartVisitor;
+
+#[cfg(feature = "serde")]
+impl <'de> serde::de::Visitor<'de> for VersionPartVisitor {
+  type Value = VersionPart;
+
+  fn expecting(&self, formatter : &mut fmt::Formatter) -> fmt::Result {
+    formatter.write_str("a version number or wildcard")
+  }
+
+  fn visit_u64<E>(self, number : u64) -> Result<VersionPart, E> where E : serde::de::Error {
+    if number > u32::MAX as u64 {
+      return Err(E::invalid_value(serde::de::Unexpected::Unsigned(number), &self));
+    }
+    Ok(VersionPart::Number(number as u32))
+  }
+
+  fn visit_i64<E>(self, number : i64) -> Result<VersionPart, E> where E : serde::de::Error {
+    if number < 0 {
+      return Err(E::invalid_value(serde::de::Unexpected::Signed(number), &self));
+    }
+    self.visit_u64(number as u64)
+  }
+
+  fn visit_str<E>(self, string : &str) -> Result<VersionPart, E> where E : serde::de::Error {
+    //! numbers written as strings are also accepted, `"12"`
+    if string == "*" {
+      return Ok(VersionPart::Wildcard('*'));
+    }
+    if !string.is_empty() && string.bytes().all(|b| b.is_ascii_digit()) {
+      if let Ok(number) = string.parse::<u32>() {
+        return Ok(VersionPart::Number(number));
+      }
+    }
+    Err(E::invalid_value(serde::de::Unexpected::Str(string), &self))
+  }
+
+  fn visit_enum<A>(self, data : A) -> Result<VersionPart, A::Error> where A : serde::de::EnumAccess<'de> {
+    use serde::de::VariantAccess;
+
+    match data.variant::<Variant>()? {
+      (Variant::Number, variant) => Ok(VersionPart::Number(variant.newtype_variant()?)),
+      (Variant::Wildcard, variant) => Ok(VersionPart::Wildcard(variant.newtype_variant()?)),
+    }
+  }
+}
+
+/// the variant tag, formats can write it as the index or the name
+#[cfg(feature = "serde")]
+enum Variant { Number, Wildcard }
+
+#[cfg(feature = "serde")]
+impl <'de> serde::Deserialize<'de> for Variant {
+  fn deserialize<D>(deserializer : D) -> Result<Variant, D::Error> where D : serde::Deserializer<'de> {
+    deserializer.deserialize_identifier(VariantVisitor)
+  }
+}
+
+#[cfg(feature = "serde")]
+struct VariantVisitor;
+
+#[cfg(feature = "serde")]
+impl <'de> serde::de::Visitor<'de> for VariantVisitor {
+  type Value = Variant;
+
+  fn expecting(&self, formatter : &mut fmt::Formatter) -> fmt::Result {
+    formatter.write_str("`Number` or `Wildcard`")
+  }
+
+  fn visit_u64<E>(self, index : u64) -> Result<Variant, E> where E : serde::de::Error {
+    match index {
+      0 => Ok(Variant::Number),
+      1 => Ok(Variant::Wildcard),
+      _ => Err(E::invalid_value(serde::de::Unexpected::Unsigned(index), &"variant index 0 <= i < 2")),
+    }
+  }
+
+  fn visit_str<E>(self, name : &str) -> Result<Variant, E> where E : serde::de::Error {
+    match name {
+      "Number" => Ok(Variant::Number),
+      "Wildcard" => Ok(Variant::Wildcard),
+      _ => Err(E::unknown_variant(name, &["Number", "Wildcard"])),
     }
   }
 }
@@ -0,0 +1,255 @@
+//! a borrowed **version**, for reading versions without copying the strings.
+//!
+//! `VersionRef` compares and matches the same as `Version`, but the pre-release label
+//! and build metadata point into the string it was parsed from.
+
+use core::fmt;
+use core::cmp::Ordering;
+
+use crate::prelude::*;
+use crate::version::{Version, versions_eq, versions_cmp, versions_compatible};
+use crate::versionpart::VersionPart;
+use crate::parts::Parts;
+use crate::parser::VersionParser;
+#[cfg(feature = "serde")]
+use crate::serde_format;
+
+/// a version that borrows its labels from the string it was parsed from.
+///
+/// ```
+/// # use version_lp::{Version, VersionRef};
+/// let line = String::from("2.1.0-rc.1");
+/// let version = VersionRef::from_str(&line).unwrap();
+///
+/// assert_eq!(version.pre(), Some("rc.1"));
+/// assert!(version < VersionRef::from_str("2.1.0").unwrap());
+/// assert_eq!(version.to_version(), Version::from_str("2.1.0-rc.1").unwrap());
+/// ```
+///
+/// like `Version` it isn't `Hash`, versions of different lengths can be equal.
+#[derive(Clone)]
+pub struct VersionRef<'a> {
+    parts : Parts,
+    pre : Option<&'a str>,
+    build : Option<&'a str>,
+    prefix : Option<char>,
+}
+
+impl <'a> VersionRef<'a> {
+
+    /// creates a version from its parts, used by the parser
+    pub(crate) fn from_parts(parts : Parts, pre : Option<&'a str>, build : Option<&'a str>, prefix : Option<char>) -> VersionRef<'a> {
+        VersionRef { parts, pre, build, prefix }
+    }
+
+    /// parses a version string, with the same rules as `Version::from_str`
+    ///
+    /// use `VersionParser::parse_ref` for more control, or to know why the string wasn't valid.
+    // `FromStr` can't borrow from the string it parses
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(version : &'a str) -> Option<VersionRef<'a>> {
+        VersionParser::new().parse_ref(version).ok()
+    }
+
+    /// the pre-release label, without the leading `-`
+    pub fn pre(&self) -> Option<&'a str> {
+        self.pre
+    }
+
+    /// the build metadata, without the leading `+`
+    pub fn build(&self) -> Option<&'a str> {
+        self.build
+    }
+
+    /// checks if the version is a pre-release, `1.2.3-rc.1`
+    pub fn is_prerelease(&self) -> bool {
+        self.pre.is_some()
+    }
+
+    /// the prefix the version was parsed with, `v` or `V`
+    pub fn prefix(&self) -> Option<char> {
+        self.prefix
+    }
+
+    /// checks if the version has a wildcard in it
+    pub fn has_wildcards(&self) -> bool {
+        self.parts.iter().any(VersionPart::is_wildcard)
+    }
+
+    /// checks if the version is all numbers (no explicit wildcards)
+    pub fn is_number(&self) -> bool {
+        self.parts.iter().all(VersionPart::is_number)
+    }
+
+    /// returns true if 100% wild (all defined sections are wildcards)
+    pub fn is_wildcard(&self) -> bool {
+        self.parts.iter().all(VersionPart::is_wildcard)
+    }
+
+    /// checks compatibility between versions, the same as `Version::is_compatible_with`
+    pub fn is_compatible_with(&self, other : &VersionRef) -> bool {
+        versions_compatible(&self.parts, self.pre, &other.parts, other.pre)
+    }
+
+    /// copies the version into a `Version`
+    pub fn to_version(&self) -> Version {
+        let mut version = Version::from_parts(self.parts.clone(), self.pre.map(String::from), self.build.map(String::from));
+        version.set_prefix(self.prefix);
+        version
+    }
+}
+
+impl <'a> From<&'a Version> for VersionRef<'a> {
+    fn from(version : &'a Version) -> VersionRef<'a> {
+        //! borrows the labels of the version
+        VersionRef {
+            parts : version.components().iter().copied().collect(),
+            pre : version.pre(),
+            build : version.build(),
+            prefix : version.prefix(),
+        }
+    }
+}
+
+impl <'a> PartialEq for VersionRef<'a> {
+    fn eq(&self, other : &VersionRef) -> bool {
+        //! the same as `Version`, everything after a wildcard is ignored
+        versions_eq(&self.parts, self.pre, &other.parts, other.pre)
+    }
+}
+
+impl <'a> Eq for VersionRef<'a> { }
+
+impl <'a> PartialEq<Version> for VersionRef<'a> {
+    fn eq(&self, other : &Version) -> bool {
+        versions_eq(&self.parts, self.pre, other.components(), other.pre())
+    }
+}
+
+impl <'a> PartialEq<VersionRef<'a>> for Version {
+    fn eq(&self, other : &VersionRef<'a>) -> bool {
+        versions_eq(self.components(), self.pre(), &other.parts, other.pre)
+    }
+}
+
+impl <'a> Ord for VersionRef<'a> {
+    fn cmp(&self, other : &VersionRef) -> Ordering {
+        versions_cmp(&self.parts, self.pre, &other.parts, other.pre)
+    }
+}
+
+impl <'a> PartialOrd for VersionRef<'a> {
+    fn partial_cmp(&self, other : &VersionRef) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl <'a> fmt::Display for VersionRef<'a> {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        //! prints "x.x.x-pre+build"
+        for (i, part) in self.parts.iter().enumerate() {
+            if i > 0 { f.write_str(".")?; }
+            write!(f, "{}", part)?;
+        }
+        if let Some(pre) = self.pre { write!(f, "-{}", pre)?; }
+        if let Some(build) = self.build { write!(f, "+{}", build)?; }
+        Ok(())
+    }
+}
+
+impl <'a> fmt::Debug for VersionRef<'a> {
+    fn fmt(&self, f : &mut fmt::Formatter) -> fmt::Result {
+        //! prints "VersionRef (x.x.x)"
+        write!(f, "VersionRef ({})", self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl <'a> serde::Serialize for VersionRef<'a> {
+    fn serialize<S>(&self, serializer : S) -> Result<S::Ok, S::Error> where S : serde::Serializer {
+        //! serializes the same as `Version`, "x.x.x" or the compact tuple
+        if serializer.is_human_readable() && serde_format::has_custom_wildcard(&self.parts) {
+            serde_format::dotted::serialize(&self.to_version(), serializer)
+        } else if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serde_format::compact::serialize_parts(&self.parts, self.pre, self.build, serializer)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl <'de : 'a, 'a> serde::Deserialize<'de> for VersionRef<'a> {
+    fn deserialize<D>(deserializer : D) -> Result<VersionRef<'a>, D::Error> where D : serde::Deserializer<'de> {
+        //! only borrowed strings can be used, so the string representations of
+        //! `serde_format` are accepted but not the maps or sequences
+        serde_format::deserialize_ref(deserializer)
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////////////////
+// TESTS GO HERE
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn borrows_labels() {
+        let line = String::from("v1.2.3-rc.1+abc");
+        let version = VersionParser::new().allow_prefix(true).parse_ref(&line).unwrap();
+
+        assert_eq!(version.pre().unwrap().as_ptr(), line[7..].as_ptr());
+        assert_eq!(version.build(), Some("abc"));
+        assert_eq!(version.to_version().to_prefixed_string(), "v1.2.3-rc.1+abc");
+        assert_eq!(version.to_string(), "1.2.3-rc.1+abc");
+    }
+
+    #[test]
+    fn same_as_version() {
+        let list = ["1.2.3", "1.2", "1.*", "1.2.3-rc.1", "1.2.3-beta", "2.0.0", "0.9.12.1", "*"];
+
+        for a in list.iter() {
+            for b in list.iter() {
+                let (owned_a, owned_b) = (Version::from_str(a).unwrap(), Version::from_str(b).unwrap());
+                let (ref_a, ref_b) = (VersionRef::from_str(a).unwrap(), VersionRef::from_str(b).unwrap());
+
+                assert_eq!(ref_a == ref_b, owned_a == owned_b, "{} == {}", a, b);
+                assert_eq!(ref_a.cmp(&ref_b), owned_a.cmp(&owned_b), "{} cmp {}", a, b);
+                assert_eq!(ref_a.is_compatible_with(&ref_b), owned_a.is_compatible_with(&owned_b), "{} compatible {}", a, b);
+                assert!(ref_a == owned_a && owned_b == ref_b);
+                assert!(VersionRef::from(&owned_a) == ref_a);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_borrow() {
+        use serde::Deserialize;
+        use serde_test::{Token, Configure, Readable, assert_de_tokens, assert_de_tokens_error, assert_ser_tokens, assert_tokens};
+
+        #[derive(Deserialize, PartialEq, Debug)]
+        struct Release<'a> {
+            #[serde(borrow)]
+            version : VersionRef<'a>,
+        }
+
+        let version = VersionRef::from_str("1.2.3-rc.1").unwrap().readable();
+        assert_ser_tokens(&version, &[Token::Str("1.2.3-rc.1")]);
+        assert_de_tokens(&version, &[Token::BorrowedStr("1.2.3-rc.1")]);
+
+        assert_tokens(&VersionRef::from_str("1.*").unwrap().compact(), &[
+            Token::Tuple { len : 4 },
+            Token::Seq { len : Some(1) }, Token::U32(1), Token::SeqEnd,
+            Token::Some, Token::Str("*"),
+            Token::None,
+            Token::None,
+            Token::TupleEnd,
+        ]);
+        assert_de_tokens_error::<Readable<Release>>(&[
+            Token::Struct { name : "Release", len : 1 },
+            Token::Str("version"), Token::String("1.2.3"),
+        ], "invalid type: string \"1.2.3\", expected a borrowed version string or compact version tuple");
+    }
+}